@@ -0,0 +1,47 @@
+#!/bin/sh
+# Starts the service of this Frontwork desktop build and opens it in a local webview window.
+# Generated by "frontwork build --platform desktop"
+
+cd "$(dirname "$0")" || exit 1
+URL="http://localhost:{{PORT}}"
+
+# "frontwork build" names the binaries main.service-{target} if it compiled for several targets
+SERVICE=./main.service
+if [ ! -x "$SERVICE" ]; then
+    case "$(uname -s)" in
+        Darwin) SERVICE="./main.service-$(uname -m | sed 's/arm64/aarch64/')-apple-darwin" ;;
+        *) SERVICE="./main.service-$(uname -m)-unknown-linux-gnu" ;;
+    esac
+fi
+
+"$SERVICE" &
+SERVICE_PID=$!
+trap 'kill $SERVICE_PID 2>/dev/null' EXIT INT TERM
+
+# wait until the service accepts connections
+if command -v curl >/dev/null 2>&1; then
+    i=0
+    while [ $i -lt 50 ] && ! curl -s -o /dev/null "$URL"; do
+        sleep 0.1
+        i=$((i + 1))
+    done
+else
+    sleep 1
+fi
+
+# a chromium based browser in app mode gives a window without browser UI
+for browser in chromium chromium-browser google-chrome google-chrome-stable microsoft-edge brave-browser; do
+    if command -v "$browser" >/dev/null 2>&1; then
+        "$browser" --app="$URL" --user-data-dir="${XDG_DATA_HOME:-$HOME/.local/share}/frontwork-desktop/{{APP_NAME}}"
+        exit $?
+    fi
+done
+
+if command -v xdg-open >/dev/null 2>&1; then
+    xdg-open "$URL"
+elif command -v open >/dev/null 2>&1; then
+    open "$URL"
+else
+    echo "Open $URL in your browser"
+fi
+wait $SERVICE_PID
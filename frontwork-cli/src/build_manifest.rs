@@ -0,0 +1,110 @@
+use std::{fs, io, path::Path, time::Duration};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+
+/// FrontworkWebservice does not serve it; listed in BUILD_METADATA_FILES of frontwork-service.ts
+pub const BUILD_MANIFEST_FILENAME: &str = "build-manifest.json";
+
+/// Describes the output of `frontwork build`; written as build-manifest.json into the dist folder.
+#[derive(Serialize, Deserialize)]
+pub struct BuildManifest {
+    pub cli_version: String,
+    pub deno_version: Option<String>,
+    pub environment: String,
+    pub platform: String,
+    pub targets: Vec<String>,
+    pub build_duration_ms: u64,
+    pub files: Vec<BuildManifestFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BuildManifestFile {
+    /// Path relative to the dist folder, separated by "/"
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+impl BuildManifest {
+    pub fn from_dist_path(dist_path: &Path, environment: &str, platform: &str, targets: &[String], build_duration: Duration) -> io::Result<BuildManifest> {
+        let mut files = Vec::new();
+        collect_files(dist_path, dist_path, &mut files)?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(BuildManifest {
+            cli_version: env!("CARGO_PKG_VERSION").to_string(),
+            deno_version: crate::deno::version(),
+            environment: environment.to_string(),
+            platform: platform.to_string(),
+            targets: targets.to_vec(),
+            build_duration_ms: build_duration.as_millis() as u64,
+            files,
+        })
+    }
+
+    pub fn write(&self, dist_path: &Path) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(dist_path.join(BUILD_MANIFEST_FILENAME), content)
+    }
+}
+
+fn collect_files(dist_path: &Path, dir_path: &Path, files: &mut Vec<BuildManifestFile>) -> io::Result<()> {
+    for entry in fs::read_dir(dir_path)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect_files(dist_path, &path, files)?;
+        } else {
+            let relative_path = path.strip_prefix(dist_path).unwrap()
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if relative_path == BUILD_MANIFEST_FILENAME {
+                continue;
+            }
+
+            let content = fs::read(&path)?;
+            files.push(BuildManifestFile {
+                path: relative_path,
+                size: content.len() as u64,
+                sha256: format!("{:x}", Sha256::digest(&content)),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_from_dist_path() {
+        let dist_path = TempDir::new("manifest");
+        fs::create_dir_all(dist_path.join("css")).unwrap();
+        fs::write(dist_path.join("css/style.css"), "a{}").unwrap();
+        fs::write(dist_path.join(BUILD_MANIFEST_FILENAME), "{}").unwrap();
+
+        let manifest = BuildManifest::from_dist_path(&dist_path, "production", "web", &["x86_64-unknown-linux-gnu".to_string()], Duration::from_millis(1500)).unwrap();
+
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(manifest.files[0].path, "css/style.css");
+        assert_eq!(manifest.files[0].size, 3);
+        assert_eq!(manifest.files[0].sha256, format!("{:x}", Sha256::digest(b"a{}")));
+        assert_eq!(manifest.build_duration_ms, 1500);
+    }
+
+    #[test]
+    fn test_build_metadata_is_not_served() {
+        let frontwork_service = include_str!("../../frontwork-std/frontwork-service.ts");
+        let build_metadata_files = frontwork_service.lines().find(|line| line.contains("const BUILD_METADATA_FILES")).unwrap();
+        for filename in [BUILD_MANIFEST_FILENAME, crate::hash_filenames::HASHED_FILENAMES_FILENAME] {
+            assert!(build_metadata_files.contains(&format!("\"{}\"", filename)), "{}", build_metadata_files);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+
+/// Number of stderr lines of a failed step repeated below the summary table
+const STDERR_TAIL_LINES: usize = 20;
+
+pub struct StepResult {
+    pub name: String,
+    pub success: bool,
+    pub duration: Duration,
+    pub stderr: String,
+}
+
+impl StepResult {
+    /// Runs a build step inside this process and measures it
+    pub fn measure(name: impl Into<String>, step: impl FnOnce() -> Result<(), String>) -> StepResult {
+        let started = Instant::now();
+        let result = step();
+
+        StepResult {
+            name: name.into(),
+            success: result.is_ok(),
+            duration: started.elapsed(),
+            stderr: result.err().unwrap_or_default(),
+        }
+    }
+}
+
+/// A build step running in a child process. Its stderr is shown while it runs and collected for the summary.
+pub struct RunningStep {
+    handle: JoinHandle<StepResult>,
+}
+
+impl RunningStep {
+    pub fn spawn(name: impl Into<String>, command: &mut Command) -> RunningStep {
+        let name = name.into();
+        let started = Instant::now();
+        let program = command.get_program().to_string_lossy().to_string();
+        let child = command.stderr(Stdio::piped()).spawn();
+
+        let handle = thread::spawn(move || {
+            let mut child = match child {
+                Ok(child) => child,
+                Err(error) => {
+                    return StepResult {
+                        name,
+                        success: false,
+                        duration: started.elapsed(),
+                        stderr: format!("Failed to execute {}: {}. Make sure {} is installed on this machine.", program, error, program),
+                    };
+                }
+            };
+
+            let mut stderr = String::new();
+            if let Some(child_stderr) = child.stderr.take() {
+                for line in BufReader::new(child_stderr).lines().map_while(Result::ok) {
+                    eprintln!("{}", line);
+                    stderr.push_str(&line);
+                    stderr.push('\n');
+                }
+            }
+
+            let success = match child.wait() {
+                Ok(status) => status.success(),
+                Err(error) => {
+                    stderr.push_str(&error.to_string());
+                    false
+                }
+            };
+
+            StepResult { name, success, duration: started.elapsed(), stderr }
+        });
+
+        RunningStep { handle }
+    }
+
+    pub fn wait(self) -> StepResult {
+        self.handle.join().expect("build step thread panicked")
+    }
+}
+
+#[derive(Default)]
+pub struct BuildReport {
+    pub steps: Vec<StepResult>,
+}
+
+impl BuildReport {
+    pub fn add(&mut self, step: StepResult) {
+        self.steps.push(step);
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.steps.iter().all(|step| step.success)
+    }
+
+    pub fn print_summary(&self) {
+        let name_width = self.steps.iter().map(|step| step.name.len()).max().unwrap_or(0).max(16);
+        println!("\n{:<name_width$} {:<8} {:>10}", "Step", "Status", "Duration");
+        for step in &self.steps {
+            println!(
+                "{:<name_width$} {:<8} {:>9.2}s",
+                step.name,
+                if step.success { "ok" } else { "FAILED" },
+                step.duration.as_secs_f64()
+            );
+        }
+
+        for step in self.steps.iter().filter(|step| !step.success) {
+            let lines: Vec<&str> = step.stderr.lines().collect();
+            println!("\n{} failed:", step.name);
+            for line in &lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..] {
+                println!("  {}", line);
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collects_step_results() {
+        let mut report = BuildReport::default();
+        report.add(RunningStep::spawn("ok", Command::new("sh").arg("-c").arg("exit 0")).wait());
+        assert!(report.is_success());
+
+        report.add(RunningStep::spawn("failing", Command::new("sh").arg("-c").arg("echo broken >&2; exit 3")).wait());
+        report.add(StepResult::measure("css", || Err("Unable to compile sass".to_string())));
+        assert!(!report.is_success());
+        assert_eq!(report.steps[1].stderr, "broken\n");
+        assert!(!report.steps[2].success);
+    }
+}
@@ -0,0 +1,321 @@
+use clap::{builder::PossibleValuesParser, Args, CommandFactory, Parser, Subcommand};
+use crate::environment_platform::{Environment, Platform};
+
+
+#[derive(Parser)]
+#[command(
+    name = "frontwork",
+    about = "-- The TypeScript Framework using Deno & Webassembly --",
+    before_help = concat!("Frontwork CLI Tool v", env!("CARGO_PKG_VERSION"), " by LuceusXylian <luceusxylian@gmail.com> and frontwork-org <https://github.com/frontwork-org> Contributors"),
+    disable_version_flag = true,
+)]
+pub struct Cli {
+    /// Print the version of this CLI tool
+    #[arg(short = 'V', long, global = true)]
+    pub version: bool,
+
+    #[command(subcommand)]
+    pub command: Option<SubCommand>,
+}
+
+#[derive(Subcommand)]
+pub enum SubCommand {
+    /// Install required dependencies to develop with Frontwork
+    Install {
+        /// Version or version range of deno, e.g. 2.1.4 or ^2.1; defaults to engines.deno in package.json, otherwise the latest release
+        #[arg(long)]
+        deno_version: Option<String>,
+        /// Install deno into .frontwork/toolchain of the project in the current directory instead of ~/.deno
+        #[arg(long)]
+        project: bool,
+        /// Only print the lines that put deno on PATH instead of adding them to the shell profile
+        #[arg(long)]
+        no_modify_path: bool,
+    },
+    /// Create a new project in the current directory
+    Init,
+    /// Create a new folder in the current directory and then execute init
+    New {
+        /// Name of the new project
+        name: Option<String>,
+    },
+    /// Create or remove a component
+    Component {
+        #[command(subcommand)]
+        command: ComponentCommand,
+    },
+    /// Create a subservice, document builder or locale and register it in the project
+    Generate {
+        #[command(subcommand)]
+        kind: GenerateKind,
+    },
+    /// Run the script of the entered name in package.json
+    Run {
+        /// Name of the script in package.json
+        script: Option<String>,
+    },
+    /// Run main.testworker.ts
+    Test,
+    /// Build the application to the dist folder
+    Build(BuildOptions),
+    /// Start development server and build the application on changes
+    Watch,
+    /// Update Frontwork dependencies to the current version of this CLI tool
+    Update,
+    /// Replace this CLI tool with its latest release
+    SelfUpdate,
+    /// Remove the deno installed by 'frontwork install' and its lines in the shell profiles
+    Uninstall {
+        /// Remove the deno in .frontwork/toolchain of the project in the current directory instead of ~/.deno
+        #[arg(long)]
+        project: bool,
+    },
+    /// Check whether deno and the project in the current directory are ready to use
+    Doctor {
+        /// Print the results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ComponentCommand {
+    /// Create a new component
+    #[command(alias = "add")]
+    New {
+        /// Name of the component; use slashes for nested components, e.g. admin/users/list
+        name: Option<String>,
+        /// Path of the route to the component in routes.ts, e.g. /users; defaults to the name in kebab-case, e.g. /admin/users/list
+        #[arg(long)]
+        path: Option<String>,
+        /// Name of the template in templates/component of the project; defaults to templates/component/default, otherwise the built-in template
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Remove a component
+    Remove {
+        /// Name of the component
+        name: Option<String>,
+    },
+    /// Rename or move a component and rewrite all references to it
+    #[command(alias = "move")]
+    Rename {
+        /// Current name of the component
+        name: Option<String>,
+        /// New name of the component, e.g. admin/users/list
+        new_name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GenerateKind {
+    /// Create a FrontworkSubservice in src/subservices and add it to the service entry with add_subservice
+    Subservice {
+        /// Name of the subservice
+        name: String,
+        /// Name of the template in templates/subservice of the project
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Create a before_route event in src/middlewares and chain it into the middleware of routes.ts
+    Middleware {
+        /// Name of the middleware
+        name: String,
+        /// Name of the template in templates/middleware of the project
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Create a DocumentBuilder subclass in src/document_builders
+    DocumentBuilder {
+        /// Name of the document builder
+        name: String,
+        /// Name of the template in templates/document_builder of the project
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Create src/i18n/{locale}.json from the translations of the first locale and register it in src/i18n.ts
+    Locale {
+        /// Locale code, e.g. fr or pt-BR
+        locale: String,
+    },
+}
+
+impl ComponentCommand {
+    pub fn name(&self) -> Option<&String> {
+        match self {
+            ComponentCommand::New { name, .. } | ComponentCommand::Remove { name } | ComponentCommand::Rename { name, .. } => name.as_ref(),
+        }
+    }
+}
+
+/// Targets supported by `deno compile --target`
+pub const DENO_COMPILE_TARGETS: [&str; 5] = [
+    "x86_64-unknown-linux-gnu",
+    "aarch64-unknown-linux-gnu",
+    "x86_64-pc-windows-msvc",
+    "x86_64-apple-darwin",
+    "aarch64-apple-darwin",
+];
+
+#[derive(Args)]
+pub struct BuildOptions {
+    #[command(flatten)]
+    environment: EnvironmentFlags,
+
+    /// Platform to build for; selects environment.{environment}.{platform}.ts and dist/{environment}-{platform}
+    #[arg(long, value_enum, default_value_t = Platform::Web)]
+    pub platform: Platform,
+
+    /// Target triples the service binary is compiled for, separated by commas; defaults to build.default_target of frontwork.toml.
+    /// With more than one target the binaries are named main.service-{target}
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = PossibleValuesParser::new(DENO_COMPILE_TARGETS),
+    )]
+    target: Vec<String>,
+
+    /// Compile the service binary for every target supported by deno
+    #[arg(long, conflicts_with = "target")]
+    all_targets: bool,
+
+    /// Add a content hash to the filenames of style.css, main.client.js and the assets
+    #[arg(long)]
+    pub hash_filenames: bool,
+}
+
+impl BuildOptions {
+    pub fn targets(&self, default_target: &str) -> Vec<String> {
+        if self.all_targets {
+            DENO_COMPILE_TARGETS.iter().map(|target| target.to_string()).collect()
+        } else if self.target.is_empty() {
+            vec![default_target.to_string()]
+        } else {
+            let mut targets: Vec<String> = Vec::new();
+            for target in &self.target {
+                if !targets.contains(target) {
+                    targets.push(target.clone());
+                }
+            }
+            targets
+        }
+    }
+
+    pub fn environment(&self) -> Environment {
+        if self.environment.staging {
+            Environment::Staging
+        } else if self.environment.development {
+            Environment::Development
+        } else {
+            Environment::Production
+        }
+    }
+}
+
+#[derive(Args)]
+#[group(multiple = false)]
+struct EnvironmentFlags {
+    /// Build for production (default)
+    #[arg(long)]
+    production: bool,
+    /// Build for staging
+    #[arg(long)]
+    staging: bool,
+    /// Build for development
+    #[arg(long)]
+    development: bool,
+}
+
+/// Moves flags that were entered before the subcommand behind it, so that
+/// `frontwork --staging build` is parsed the same as `frontwork build --staging`.
+pub fn hoist_leading_flags(args: Vec<String>) -> Vec<String> {
+    let root = Cli::command();
+    let Some(subcommand_index) = args.iter()
+        .skip(1)
+        .position(|arg| !arg.starts_with('-') && root.find_subcommand(arg).is_some())
+        .map(|i| i + 1)
+    else {
+        return args;
+    };
+
+    // descend into nested subcommands like `component new`
+    let mut command = root.find_subcommand(&args[subcommand_index]).unwrap();
+    let mut last_subcommand_index = subcommand_index;
+    while let Some(nested) = args.get(last_subcommand_index + 1).and_then(|arg| command.find_subcommand(arg)) {
+        command = nested;
+        last_subcommand_index += 1;
+    }
+
+    let mut hoisted = Vec::with_capacity(args.len());
+    hoisted.push(args[0].clone());
+    hoisted.extend_from_slice(&args[subcommand_index..=last_subcommand_index]);
+    hoisted.extend_from_slice(&args[1..subcommand_index]);
+    hoisted.extend_from_slice(&args[last_subcommand_index + 1..]);
+    hoisted
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        let args = args.iter().map(|s| s.to_string()).collect();
+        Cli::try_parse_from(hoist_leading_flags(args))
+    }
+
+    #[test]
+    fn test_build_options() {
+        let Some(SubCommand::Build(options)) = parse(&["frontwork", "build", "--staging", "--target", "aarch64-unknown-linux-gnu"]).unwrap().command else {
+            panic!("expected build subcommand");
+        };
+        assert!(options.environment() == Environment::Staging);
+        assert_eq!(options.targets("x86_64-unknown-linux-gnu"), ["aarch64-unknown-linux-gnu"]);
+
+        let Some(SubCommand::Build(options)) = parse(&["frontwork", "build"]).unwrap().command else {
+            panic!("expected build subcommand");
+        };
+        assert!(options.environment() == Environment::Production);
+        assert!(options.platform == Platform::Web);
+        assert_eq!(options.targets("aarch64-apple-darwin"), ["aarch64-apple-darwin"]);
+    }
+
+    #[test]
+    fn test_build_targets() {
+        let Some(SubCommand::Build(options)) = parse(&["frontwork", "build", "--target", "x86_64-unknown-linux-gnu,aarch64-unknown-linux-gnu"]).unwrap().command else {
+            panic!("expected build subcommand");
+        };
+        assert_eq!(options.targets("x86_64-unknown-linux-gnu"), ["x86_64-unknown-linux-gnu", "aarch64-unknown-linux-gnu"]);
+
+        let Some(SubCommand::Build(options)) = parse(&["frontwork", "build", "--all-targets"]).unwrap().command else {
+            panic!("expected build subcommand");
+        };
+        assert_eq!(options.targets("x86_64-unknown-linux-gnu").len(), DENO_COMPILE_TARGETS.len());
+
+        assert!(parse(&["frontwork", "build", "--target", "x86_64-unknown-linux-gnu,riscv64-unknown-linux-gnu"]).is_err());
+        assert!(parse(&["frontwork", "build", "--target", "x86_64-unknown-linux-gnu", "--all-targets"]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_conflicting_flags() {
+        assert!(parse(&["frontwork", "build", "--prodcution"]).is_err());
+        assert!(parse(&["frontwork", "build", "--staging", "--production"]).is_err());
+        assert!(parse(&["frontwork", "watch", "--staging"]).is_err());
+    }
+
+    #[test]
+    fn test_flags_before_subcommand() {
+        let Some(SubCommand::Build(options)) = parse(&["frontwork", "--development", "build"]).unwrap().command else {
+            panic!("expected build subcommand");
+        };
+        assert!(options.environment() == Environment::Development);
+
+        let Some(SubCommand::Component { command }) = parse(&["frontwork", "component", "add", "my_component"]).unwrap().command else {
+            panic!("expected component subcommand");
+        };
+        assert!(matches!(command, ComponentCommand::New { .. }));
+        assert_eq!(command.name().map(String::as_str), Some("my_component"));
+
+        assert!(parse(&["frontwork", "--version"]).unwrap().version);
+    }
+}
@@ -0,0 +1,393 @@
+use std::{fs, path::{Path, PathBuf}};
+use convert_case::{Case, Casing};
+use regex::Regex;
+use crate::{config::FrontworkConfig, routes_file};
+use crate::scaffold::{self, normalize, read_file, relative_import_path, FileTransaction};
+use crate::typescript::{apply_edits, Tokens};
+
+
+/// A component below src/components, e.g. `admin/users/list` in src/components/admin/users/list/list.ts
+#[derive(Debug, PartialEq)]
+pub struct ComponentName {
+    /// The directories below src/components in snake_case
+    pub path: String,
+    /// The last directory, which is also the name of the .ts and .scss file
+    pub name: String,
+    /// e.g. AdminUsersListComponent
+    pub class_name: String,
+}
+
+impl ComponentName {
+    pub fn parse(input: &str) -> Result<ComponentName, String> {
+        let segments: Vec<String> = input.trim().trim_matches('/').split('/')
+            .map(|segment| segment.to_case(Case::Snake))
+            .collect();
+        if segments.iter().any(|segment| segment.is_empty() || segment.starts_with('.')) {
+            return Err(format!("Invalid component name \"{}\". Use names like users or admin/users/list.", input));
+        }
+
+        let class_name = segments.iter().map(|segment| segment.to_case(Case::UpperCamel)).collect::<String>() + "Component";
+        Ok(ComponentName {
+            path: segments.join("/"),
+            name: segments.last().unwrap().clone(),
+            class_name,
+        })
+    }
+
+    /// The route to the component if `--path` is not given, e.g. /admin/users/list
+    pub fn default_route_path(&self) -> String {
+        format!("/{}", self.path.split('/').map(|segment| segment.to_case(Case::Kebab)).collect::<Vec<_>>().join("/"))
+    }
+}
+
+/// The files of a component in a project
+pub struct ComponentFiles {
+    pub directory: PathBuf,
+    pub ts_file: PathBuf,
+    pub scss_file: PathBuf,
+}
+
+impl ComponentFiles {
+    pub fn new(config: &FrontworkConfig, name: &ComponentName) -> ComponentFiles {
+        let directory = Path::new(&config.components_path()).join(&name.path);
+        ComponentFiles {
+            ts_file: directory.join(format!("{}.ts", name.name)),
+            scss_file: directory.join(format!("{}.scss", name.name)),
+            directory,
+        }
+    }
+}
+
+/// The components in subdirectories of the component, e.g. admin/users for admin, in the form of `frontwork component` arguments
+fn nested_components(config: &FrontworkConfig, files: &ComponentFiles) -> Vec<String> {
+    let components_path = PathBuf::from(config.components_path());
+    let mut nested_components: Vec<String> = scaffold::files_in(&files.directory).into_iter()
+        .filter(|path| path.parent() != Some(&*files.directory))
+        // like ComponentFiles, a component is a directory with a .ts file of the same name
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ts") && path.file_stem() == path.parent().and_then(Path::file_name))
+        .filter_map(|path| path.parent()?.strip_prefix(&components_path).ok().map(|directory| directory.to_string_lossy().to_string()))
+        .collect();
+    nested_components.sort();
+    nested_components
+}
+
+pub fn routes_file_path(config: &FrontworkConfig) -> PathBuf {
+    Path::new(&config.components_path()).join("routes.ts")
+}
+
+/// The import statement of the component in the global style file
+fn style_import(config: &FrontworkConfig, files: &ComponentFiles) -> String {
+    let style_file_path = PathBuf::from(config.path(&config.build.style_entry));
+    format!("@import '{}';", relative_import_path(style_file_path.parent().unwrap(), &files.scss_file))
+}
+
+/// Creates the component from a template, imports it in routes.ts with a route to `route_path` and in the global style file
+pub fn create(config: &FrontworkConfig, name: &ComponentName, template: Option<&str>, route_path: &str) -> Result<(), String> {
+    let files = ComponentFiles::new(config, name);
+    if files.directory.exists() {
+        return Err("The componentname has been used. Please use another name.".to_string());
+    }
+
+    let placeholders = [
+        ("snake_name", name.name.clone()),
+        ("camel_name", name.class_name.trim_end_matches("Component").to_string()),
+        ("class_name", name.class_name.clone()),
+        ("path", name.path.clone()),
+        ("dependencies_path", relative_import_path(&files.directory, &Path::new(&config.src_path()).join("dependencies.ts"))),
+        ("routes_path", relative_import_path(&files.directory, &routes_file_path(config))),
+    ];
+    let rendered_files = scaffold::render_template(config, "component", template, &files.directory, &placeholders)?;
+    if !rendered_files.iter().any(|(path, _)| *path == files.ts_file) {
+        return Err(format!("The component template has no {{{{snake_name}}}}.ts, which would become {}", files.ts_file.display()));
+    }
+
+    // edit routes.ts first, so that nothing is created if the route can not be added
+    let routes_file_path = routes_file_path(config);
+    let import_path = relative_import_path(routes_file_path.parent().unwrap(), &files.ts_file);
+    let routes_file_content = routes_file::add_component(&read_file(&routes_file_path)?, &name.class_name, &import_path, route_path)
+        .map_err(|error| format!("Unable to add the component to routes.ts: {}", error))?;
+
+    let style_file_path = config.path(&config.build.style_entry);
+    let mut style_file_content = read_file(Path::new(&style_file_path))
+        .map_err(|error| format!("{}\nThe project may not be initialized.", error))?;
+    if rendered_files.iter().any(|(path, _)| *path == files.scss_file) {
+        style_file_content += &format!("\n{}", style_import(config, &files));
+    }
+
+    FileTransaction::run(|transaction| {
+        for (path, content) in &rendered_files {
+            transaction.write(path, content)?;
+        }
+        transaction.write(&routes_file_path, &routes_file_content)?;
+        transaction.write(Path::new(&style_file_path), &style_file_content)
+    }).map_err(|error| format!("The component has not been created. {}", error))
+}
+
+/// Removes the component, its route and its imports. Directories that become empty are removed as well.
+pub fn remove(config: &FrontworkConfig, name: &ComponentName) -> Result<(), String> {
+    let files = ComponentFiles::new(config, name);
+    if !files.directory.exists() {
+        return Err("The component does not exist.".to_string());
+    }
+    // removing the directory would leave their imports, routes and style imports behind
+    let nested_components = nested_components(config, &files);
+    if !nested_components.is_empty() {
+        return Err(format!("The component contains the nested components {}. Please remove them first.", nested_components.join(", ")));
+    }
+
+    let routes_file_path = routes_file_path(config);
+    let routes_file_content = routes_file::remove_component(&read_file(&routes_file_path)?, &name.class_name)
+        .map_err(|error| format!("The component has not been removed. {}", error))?;
+
+    let style_file_path = config.path(&config.build.style_entry);
+    let style_file_content = read_file(Path::new(&style_file_path))
+        .map_err(|error| format!("{}\nThe project may not be initialized.", error))?;
+    let style_import = style_import(config, &files);
+    let style_file_content = style_file_content
+        .replace(&format!("\n{}", style_import), "")
+        .replace(&style_import, "");
+
+    FileTransaction::run(|transaction| {
+        for path in scaffold::files_in(&files.directory) {
+            transaction.remove(&path)?;
+        }
+        for directory in directories_in(&files.directory) {
+            transaction.remove_dir(&directory)?;
+        }
+        transaction.write(&routes_file_path, &routes_file_content)?;
+        transaction.write(Path::new(&style_file_path), &style_file_content)
+    }).map_err(|error| format!("The component has not been removed. {}", error))?;
+
+    remove_empty_parents(&files.directory, Path::new(&config.components_path()));
+    Ok(())
+}
+
+/// Moves the component to `new_name` and renames its files and class. The imports of the moved files and of all
+/// files in src are rewritten, as well as the class name in the files that import it. Returns the touched files.
+/// Everything is rolled back if a step fails.
+pub fn rename(config: &FrontworkConfig, old_name: &ComponentName, new_name: &ComponentName) -> Result<Vec<String>, String> {
+    let old_files = ComponentFiles::new(config, old_name);
+    let new_files = ComponentFiles::new(config, new_name);
+    if !old_files.directory.exists() {
+        return Err("The component does not exist.".to_string());
+    }
+    if new_files.directory.exists() {
+        return Err("The componentname has been used. Please use another name.".to_string());
+    }
+    if new_files.directory.starts_with(&old_files.directory) {
+        return Err(format!("The component can not be moved into itself: {}", new_files.directory.display()));
+    }
+    // their directories would move along, but their class names, routes and style imports would stay the same
+    let nested_components = nested_components(config, &old_files);
+    if !nested_components.is_empty() {
+        return Err(format!("The component contains the nested components {}. Please move them out first.", nested_components.join(", ")));
+    }
+
+    // every file of the component directory and where it is moved to
+    let moves: Vec<(PathBuf, PathBuf)> = scaffold::files_in(&old_files.directory).into_iter()
+        .map(|path| {
+            let new_path = match path {
+                _ if path == old_files.ts_file => new_files.ts_file.clone(),
+                _ if path == old_files.scss_file => new_files.scss_file.clone(),
+                _ => new_files.directory.join(path.strip_prefix(&old_files.directory).unwrap()),
+            };
+            (path, new_path)
+        })
+        .collect();
+    let moved_to = |path: &Path| moves.iter().find(|(from, _)| from == path).map(|(_, to)| to.clone());
+
+    // the style entry may be outside of src
+    let mut source_files = scaffold::files_in(Path::new(&config.src_path()));
+    let style_file_path = normalize(Path::new(&config.path(&config.build.style_entry)));
+    if !source_files.contains(&style_file_path) {
+        source_files.push(style_file_path);
+    }
+
+    let mut writes = Vec::new();
+    for path in source_files {
+        let new_path = moved_to(&path).unwrap_or(path.clone());
+        let content = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("ts" | "tsx" | "scss") => read_file(&path)?,
+            _ => continue,
+        };
+
+        let new_content = if path.extension().is_some_and(|extension| extension == "scss") {
+            rewrite_style_imports(&content, &path, &new_path, &moved_to)
+        } else {
+            let tokens = Tokens::new(&content).map_err(|error| format!("{}: {}", path.display(), error))?;
+            let mut edits = Vec::new();
+            let mut imports_component = path == old_files.ts_file;
+            for index in tokens.module_specifiers() {
+                let token = tokens.tokens[index];
+                let module = &content[token.start + 1..token.end - 1];
+                if let Some(new_module) = rewrite_import(module, &path, &new_path, &moved_to) {
+                    edits.push((token.start + 1..token.end - 1, new_module));
+                }
+                imports_component |= module.starts_with('.') && normalize(&path.parent().unwrap().join(module)) == old_files.ts_file;
+            }
+            if imports_component {
+                edits.extend((0..tokens.tokens.len())
+                    .filter(|&index| tokens.is_word(index, &old_name.class_name))
+                    .map(|index| (tokens.tokens[index].start..tokens.tokens[index].end, new_name.class_name.clone())));
+            }
+            apply_edits(&content, edits)
+        };
+
+        if new_content != content {
+            writes.push((new_path, new_content));
+        }
+    }
+
+    let touched_files = FileTransaction::run(|transaction| {
+        let mut touched_files = Vec::new();
+        for (from, to) in &moves {
+            transaction.rename(from, to)?;
+            touched_files.push(format!("moved   {} -> {}", from.display(), to.display()));
+        }
+        for (path, content) in &writes {
+            transaction.write(path, content)?;
+            touched_files.push(format!("updated {}", path.display()));
+        }
+        Ok(touched_files)
+    }).map_err(|error| format!("The component has not been renamed. {}", error))?;
+
+    remove_empty_directories(&old_files.directory);
+    remove_empty_parents(&old_files.directory, Path::new(&config.components_path()));
+    Ok(touched_files)
+}
+
+/// The module specifier to use after `file` moved to `new_file`, or None if it stays the same.
+/// Only relative imports are rewritten.
+fn rewrite_import(module: &str, file: &Path, new_file: &Path, moved_to: &dyn Fn(&Path) -> Option<PathBuf>) -> Option<String> {
+    if !module.starts_with("./") && !module.starts_with("../") {
+        return None;
+    }
+
+    let target = normalize(&file.parent().unwrap().join(module));
+    let new_target = moved_to(&target);
+    if new_target.is_none() && file == new_file {
+        return None;
+    }
+    Some(relative_import_path(new_file.parent().unwrap(), &new_target.unwrap_or(target)))
+}
+
+/// Rewrites the paths of `@import`, `@use` and `@forward` in a stylesheet
+fn rewrite_style_imports(content: &str, file: &Path, new_file: &Path, moved_to: &dyn Fn(&Path) -> Option<PathBuf>) -> String {
+    let pattern = Regex::new(r#"@(import|use|forward)\s+(['"])([^'"]+)['"]"#).unwrap();
+    let edits = pattern.captures_iter(content)
+        .filter_map(|captures| {
+            let module = captures.get(3).unwrap();
+            let module_text = module.as_str();
+            // `@import 'components/list/list.scss'` is relative as well
+            let relative_module = if module_text.starts_with('.') { module_text.to_string() } else { format!("./{}", module_text) };
+            if module_text.contains(':') || module_text.starts_with('/') || module_text.starts_with('~') {
+                return None;
+            }
+            rewrite_import(&relative_module, file, new_file, moved_to).map(|new_module| (module.range(), new_module))
+        })
+        .collect();
+    apply_edits(content, edits)
+}
+
+/// `directory` and all directories in it, the deepest first
+fn directories_in(directory: &Path) -> Vec<PathBuf> {
+    let mut directories: Vec<PathBuf> = fs::read_dir(directory).into_iter().flatten().flatten()
+        .filter(|entry| entry.path().is_dir())
+        .flat_map(|entry| directories_in(&entry.path()))
+        .collect();
+    directories.push(directory.to_path_buf());
+    directories
+}
+
+/// Removes `directory` and all directories in it, if they contain no files
+fn remove_empty_directories(directory: &Path) {
+    for entry in fs::read_dir(directory).into_iter().flatten().flatten() {
+        if entry.path().is_dir() {
+            remove_empty_directories(&entry.path());
+        }
+    }
+    fs::remove_dir(directory).ok();
+}
+
+/// Removes the empty directories from `directory` up to, but not including, `stop`
+fn remove_empty_parents(directory: &Path, stop: &Path) {
+    for parent in directory.ancestors().skip(1) {
+        if parent == stop || !parent.starts_with(stop) || fs::remove_dir(parent).is_err() {
+            break;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_component_name() {
+        let name = ComponentName::parse("admin/usersList/list-all").unwrap();
+        assert_eq!(name, ComponentName { path: "admin/users_list/list_all".to_string(), name: "list_all".to_string(), class_name: "AdminUsersListListAllComponent".to_string() });
+        assert_eq!(name.default_route_path(), "/admin/users-list/list-all");
+        assert!(ComponentName::parse("admin/../users").is_err());
+        assert!(ComponentName::parse("admin//users").is_err());
+    }
+
+    #[test]
+    fn test_create_rolls_back() {
+        let project_path = TempDir::new("component-create");
+        let config = FrontworkConfig { project_path: project_path.to_string_lossy().to_string(), ..Default::default() };
+        let components_path = project_path.join("src/components");
+        fs::create_dir_all(&components_path).unwrap();
+        fs::write(project_path.join("src/style.scss"), "").unwrap();
+        fs::write(components_path.join("routes.ts"), "export const routes: Route[] = [];\n").unwrap();
+
+        // the second file of the template gets a name that is too long to be written
+        let template_path = project_path.join("templates/component/long");
+        fs::create_dir_all(&template_path).unwrap();
+        fs::write(template_path.join("{{snake_name}}.ts"), "export class {{class_name}} {}").unwrap();
+        fs::write(template_path.join(format!("{}.txt", "{{snake_name}}".repeat(15))), "").unwrap();
+
+        let error = create(&config, &ComponentName::parse("a_component_with_a_long_name").unwrap(), Some("long"), "/long").unwrap_err();
+        assert!(error.starts_with("The component has not been created. Unable to write"), "{}", error);
+        assert_eq!(fs::read_dir(&components_path).unwrap().count(), 1);
+        assert_eq!(fs::read_to_string(components_path.join("routes.ts")).unwrap(), "export const routes: Route[] = [];\n");
+        assert_eq!(fs::read_to_string(project_path.join("src/style.scss")).unwrap(), "");
+    }
+
+    #[test]
+    fn test_rename() {
+        let project_path = TempDir::new("component");
+        let config = FrontworkConfig { project_path: project_path.to_string_lossy().to_string(), ..Default::default() };
+        let components_path = project_path.join("src/components");
+        fs::create_dir_all(components_path.join("startpage")).unwrap();
+        fs::write(project_path.join("src/style.scss"), "").unwrap();
+        fs::write(components_path.join("routes.ts"), "export const routes: Route[] = [];\n").unwrap();
+        let users = ComponentName::parse("users").unwrap();
+        create(&config, &users, None, "/users").unwrap();
+        fs::write(components_path.join("startpage/startpage.ts"), "import { UsersComponent } from '../users/users.ts';\nnew UsersComponent();\n").unwrap();
+
+        // a file blocks the new directory; nothing is changed
+        fs::write(components_path.join("pages"), "").unwrap();
+        assert!(rename(&config, &users, &ComponentName::parse("pages/people").unwrap()).is_err());
+        assert!(components_path.join("users/users.ts").exists());
+        fs::remove_file(components_path.join("pages")).unwrap();
+
+        let touched_files = rename(&config, &users, &ComponentName::parse("admin/people").unwrap()).unwrap();
+        assert_eq!(touched_files.len(), 6);
+        assert!(!components_path.join("users").exists());
+        assert!(fs::read_to_string(components_path.join("admin/people/people.ts")).unwrap().contains("from \"../../../dependencies.ts\";\n\n\nexport class AdminPeopleComponent implements Component {"));
+        assert_eq!(fs::read_to_string(components_path.join("startpage/startpage.ts")).unwrap(), "import { AdminPeopleComponent } from '../admin/people/people.ts';\nnew AdminPeopleComponent();\n");
+        assert_eq!(fs::read_to_string(components_path.join("routes.ts")).unwrap(), "import { AdminPeopleComponent } from \"./admin/people/people.ts\";\nexport const routes: Route[] = [\n\tnew Route(\"/users\", AdminPeopleComponent),\n];\n");
+        assert_eq!(fs::read_to_string(project_path.join("src/style.scss")).unwrap(), "\n@import './components/admin/people/people.scss';");
+
+        let admin = ComponentName::parse("admin").unwrap();
+        fs::write(components_path.join("admin/admin.ts"), "export class AdminComponent {}\n").unwrap();
+        assert_eq!(remove(&config, &admin).unwrap_err(), "The component contains the nested components admin/people. Please remove them first.");
+        assert_eq!(rename(&config, &admin, &ComponentName::parse("staff").unwrap()).unwrap_err(), "The component contains the nested components admin/people. Please move them out first.");
+        remove(&config, &ComponentName::parse("admin/people").unwrap()).unwrap();
+        remove(&config, &admin).unwrap();
+        assert!(!components_path.join("admin").exists());
+        assert_eq!(fs::read_to_string(components_path.join("routes.ts")).unwrap(), "export const routes: Route[] = [\n];\n");
+    }
+}
@@ -0,0 +1,231 @@
+use std::{fs, path::Path};
+use serde::Deserialize;
+use crate::{cli::DENO_COMPILE_TARGETS, deno, reload};
+
+
+pub const CONFIG_FILENAME: &str = "frontwork.toml";
+
+/// Settings of frontwork.toml, or of the "frontwork" section in package.json if there is no frontwork.toml.
+/// Every key is optional; the defaults describe the layout of the project template.
+#[derive(Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FrontworkConfig {
+    pub build: BuildConfig,
+    pub watch: WatchConfig,
+    pub deno: DenoConfig,
+    /// Directory of the project; all paths of the config are relative to it
+    #[serde(skip)]
+    pub project_path: String,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuildConfig {
+    /// Directory with the components, dependencies.ts and main.testworker.ts; watched by `frontwork watch`
+    pub src_dir: String,
+    pub service_entry: String,
+    /// Deno script that bundles the client with esbuild; receives the dist folder as first argument
+    pub client_bundle: String,
+    /// Compiled to css/style.css
+    pub style_entry: String,
+    /// Additional SCSS files; each one is compiled to css/{file stem}.css
+    pub extra_style_entries: Vec<String>,
+    pub assets_dir: String,
+    pub environments_dir: String,
+    /// The builds are written to {dist_dir}/{environment}-{platform}
+    pub dist_dir: String,
+    pub deno_config: String,
+    /// Used by `frontwork build` without --target
+    pub default_target: String,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct WatchConfig {
+    /// Time to wait for further changes before rebuilding
+    pub debounce_ms: u64,
+    /// Port of the reload channel; passed to the service, which tells it the client
+    pub reload_port: u16,
+    /// Address the reload channel listens on; 0.0.0.0 to open the page through a LAN IP or hostname
+    pub reload_address: String,
+}
+
+#[derive(Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DenoConfig {
+    pub permissions: DenoPermissions,
+}
+
+/// Permissions of the deno processes, e.g. `["read", "write=./uploads", "--allow-ffi"]`.
+/// They are normalized to deno flags while the config is read.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DenoPermissions {
+    /// The service compiled by `frontwork build` and run by `frontwork watch`
+    pub service: Vec<String>,
+    /// The client bundle script
+    pub client: Vec<String>,
+    /// main.testworker.ts run by `frontwork test`
+    pub test: Vec<String>,
+}
+
+impl Default for BuildConfig {
+    fn default() -> Self {
+        Self {
+            src_dir: "src".to_string(),
+            service_entry: "src/main.service.ts".to_string(),
+            client_bundle: "bundle.ts".to_string(),
+            style_entry: "src/style.scss".to_string(),
+            extra_style_entries: Vec::new(),
+            assets_dir: "src/assets".to_string(),
+            environments_dir: "src/environments".to_string(),
+            dist_dir: "dist".to_string(),
+            deno_config: "deno.jsonc".to_string(),
+            default_target: "x86_64-unknown-linux-gnu".to_string(),
+        }
+    }
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self { debounce_ms: 50, reload_port: reload::RELOAD_PORT, reload_address: "127.0.0.1".to_string() }
+    }
+}
+
+impl Default for DenoPermissions {
+    fn default() -> Self {
+        let flags = |flags: &[&str]| flags.iter().map(|flag| flag.to_string()).collect();
+        Self {
+            service: flags(&["--allow-read", "--allow-net", "--allow-env"]),
+            client: flags(&["--allow-read", "--allow-write", "--allow-net", "--allow-env", "--allow-run"]),
+            test: flags(&["--allow-read", "--allow-net", "--allow-env"]),
+        }
+    }
+}
+
+impl FrontworkConfig {
+    /// Reads frontwork.toml, otherwise the "frontwork" section of package.json, otherwise returns the defaults
+    pub fn from_project_path(project_path: &str) -> Result<FrontworkConfig, String> {
+        let project_path = Path::new(project_path);
+        let config_path = project_path.join(CONFIG_FILENAME);
+        let package_json_path = project_path.join("package.json");
+
+        let mut config = if config_path.exists() {
+            let content = fs::read_to_string(&config_path)
+                .map_err(|error| format!("Can not open {}: {}", config_path.display(), error))?;
+            toml::from_str(&content)
+                .map_err(|error| format!("Unable to parse {}: {}", config_path.display(), error))?
+        } else if package_json_path.exists() {
+            let content = fs::read_to_string(&package_json_path)
+                .map_err(|error| format!("Can not open {}: {}", package_json_path.display(), error))?;
+            let package_json: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|error| format!("Unable to parse {}: {}", package_json_path.display(), error))?;
+            match package_json.get("frontwork") {
+                Some(section) => FrontworkConfig::deserialize(section)
+                    .map_err(|error| format!("Unable to parse the \"frontwork\" section of {}: {}", package_json_path.display(), error))?,
+                None => FrontworkConfig::default(),
+            }
+        } else {
+            FrontworkConfig::default()
+        };
+
+        if !DENO_COMPILE_TARGETS.contains(&config.build.default_target.as_str()) {
+            return Err(format!(
+                "build.default_target \"{}\" is not supported. Possible values: {}",
+                config.build.default_target,
+                DENO_COMPILE_TARGETS.join(", ")
+            ));
+        }
+
+        let permissions = &mut config.deno.permissions;
+        for (profile, permissions) in [("service", &mut permissions.service), ("client", &mut permissions.client), ("test", &mut permissions.test)] {
+            *permissions = permissions.iter()
+                .map(|permission| deno::permission_flag(permission))
+                .collect::<Result<_, _>>()
+                .map_err(|error| format!("deno.permissions.{}: {}", profile, error))?;
+        }
+
+        config.project_path = project_path.to_string_lossy().to_string();
+        Ok(config)
+    }
+
+    /// Reads the config of the frontwork project in `project_path`; fails if package.json or the components directory is missing
+    pub fn from_project_directory(project_path: &str) -> Result<FrontworkConfig, String> {
+        if !Path::new(project_path).join("package.json").exists() {
+            return Err(format!("{} has no package.json", project_path));
+        }
+
+        let config = Self::from_project_path(project_path)?;
+        if !Path::new(&config.components_path()).exists() {
+            return Err(format!("The directory {} does not exist", config.components_path()));
+        }
+
+        Ok(config)
+    }
+
+    /// Resolves a path of the config against the project directory
+    pub fn path(&self, relative_path: &str) -> String {
+        Path::new(&self.project_path).join(relative_path).to_string_lossy().to_string()
+    }
+
+    pub fn src_path(&self) -> String {
+        self.path(&self.build.src_dir)
+    }
+
+    pub fn components_path(&self) -> String {
+        format!("{}/components", self.src_path())
+    }
+
+    pub fn deno_config_path(&self) -> String {
+        self.path(&self.build.deno_config)
+    }
+
+    /// {dist_dir}/{environment}-{platform}
+    pub fn dist_path(&self, environment: &str, platform: &str) -> String {
+        format!("{}/{}-{}", self.path(&self.build.dist_dir), environment, platform)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_from_project_path() {
+        let project_path = TempDir::new("config");
+        let project_path_str = project_path.to_str().unwrap();
+
+        // defaults without any config
+        let config = FrontworkConfig::from_project_path(project_path_str).unwrap();
+        assert_eq!(config.build, BuildConfig::default());
+        assert_eq!(config.dist_path("production", "web"), format!("{}/dist/production-web", project_path_str));
+
+        fs::write(project_path.join("package.json"), r#"{ "name": "app", "frontwork": { "build": { "dist_dir": "../../out" } } }"#).unwrap();
+        let config = FrontworkConfig::from_project_path(project_path_str).unwrap();
+        assert_eq!(config.build.dist_dir, "../../out");
+        assert_eq!(config.build.src_dir, "src");
+
+        // frontwork.toml takes precedence over package.json
+        fs::write(project_path.join(CONFIG_FILENAME), "[build]\nsrc_dir = \"app\"\nextra_style_entries = [\"app/admin.scss\"]\n\n[deno.permissions]\nservice = [\"read=./data\", \"--allow-ffi\"]\n").unwrap();
+        let config = FrontworkConfig::from_project_path(project_path_str).unwrap();
+        assert_eq!(config.build.src_dir, "app");
+        assert_eq!(config.build.dist_dir, "dist");
+        assert_eq!(config.build.extra_style_entries, ["app/admin.scss"]);
+        assert_eq!(config.deno.permissions.service, ["--allow-read=./data", "--allow-ffi"]);
+        assert_eq!(config.deno.permissions.test, DenoPermissions::default().test);
+        assert_eq!(config.watch, WatchConfig::default());
+
+        fs::write(project_path.join(CONFIG_FILENAME), "[watch]\nreload_port = 35800\nreload_address = \"0.0.0.0\"\n").unwrap();
+        let config = FrontworkConfig::from_project_path(project_path_str).unwrap();
+        assert_eq!((config.watch.reload_port, config.watch.reload_address.as_str(), config.watch.debounce_ms), (35800, "0.0.0.0", 50));
+
+        fs::write(project_path.join(CONFIG_FILENAME), "[build]\nsrc_dri = \"app\"\n").unwrap();
+        assert!(FrontworkConfig::from_project_path(project_path_str).is_err());
+        fs::write(project_path.join(CONFIG_FILENAME), "[deno.permissions]\nservice = [\"--allow-sqlite\"]\n").unwrap();
+        assert!(FrontworkConfig::from_project_path(project_path_str).is_err());
+        fs::write(project_path.join(CONFIG_FILENAME), "[build]\ndefault_target = \"riscv64gc-unknown-linux-gnu\"\n").unwrap();
+        assert!(FrontworkConfig::from_project_path(project_path_str).is_err());
+    }
+}
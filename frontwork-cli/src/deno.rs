@@ -0,0 +1,161 @@
+use std::{env, ffi::{OsStr, OsString}, path::{Path, PathBuf}, process::Command};
+use regex::Regex;
+use semver::{Version, VersionReq};
+use crate::config::FrontworkConfig;
+
+
+/// Directory inside the project that `frontwork install --project` installs deno into
+pub const PROJECT_TOOLCHAIN_DIR: &str = ".frontwork/toolchain";
+
+/// Permissions accepted by `deno run` and `deno compile`, without the `--allow-` / `--deny-` prefix
+const DENO_PERMISSIONS: [&str; 9] = ["read", "write", "net", "env", "sys", "run", "ffi", "import", "all"];
+
+/// Every deno process of the CLI is created here: `deno {subcommand} -c {deno_config} {permissions}`.
+/// The caller appends the remaining flags, the entry file and its arguments.
+pub fn command(subcommand: &str, config: &FrontworkConfig, permissions: &[String]) -> Command {
+    let mut command = Command::new(executable());
+    command
+        .arg(subcommand)
+        .arg("-c")
+        .arg(config.deno_config_path())
+        .args(permissions);
+    command
+}
+
+/// The deno executable of every deno process: the toolchain of the project (`frontwork install --project`)
+/// in the current directory or one of its parents, otherwise $DENO_INSTALL/bin/deno, otherwise deno from PATH
+pub fn executable() -> PathBuf {
+    executable_from(&env::current_dir().unwrap_or_default(), env::var_os("DENO_INSTALL"))
+}
+
+fn executable_from(current_dir: &Path, deno_install: Option<OsString>) -> PathBuf {
+    let project_toolchain = current_dir.ancestors()
+        .map(|dir| dir.join(PROJECT_TOOLCHAIN_DIR).join("bin/deno"))
+        .find(|path| path.is_file());
+    let deno_install = deno_install
+        .map(|deno_install| Path::new(&deno_install).join("bin/deno"))
+        .filter(|path| path.is_file());
+
+    project_toolchain.or(deno_install).unwrap_or(PathBuf::from("deno"))
+}
+
+/// Returns the version of the installed deno, e.g. "2.2.3"
+pub fn version() -> Option<String> {
+    binary_version(executable())
+}
+
+/// Returns the version of the deno executable at `program`
+pub fn binary_version(program: impl AsRef<OsStr>) -> Option<String> {
+    let output = Command::new(program).arg("--version").output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // first line looks like: deno 2.2.3 (stable, release, x86_64-unknown-linux-gnu)
+    stdout.lines().next()?.split_whitespace().nth(1).map(|v| v.to_string())
+}
+
+/// A version range in the npm syntax of `engines.deno` in package.json, e.g. "^1.44.0" or ">=1.40 <3 || 3.1.x"
+pub struct VersionRange {
+    alternatives: Vec<VersionReq>,
+}
+
+impl VersionRange {
+    pub fn parse(range: &str) -> Result<VersionRange, String> {
+        let comparator_separator = Regex::new(r"([0-9*xX])\s+([<>=^~0-9])").unwrap();
+        let alternatives = range.split("||").map(|alternative| {
+            let alternative = alternative.trim();
+            // npm separates comparators by spaces, cargo by commas; a bare version means exactly that version in npm
+            let alternative = comparator_separator.replace_all(alternative, "$1, $2");
+            let alternative = alternative.split(", ")
+                .map(|comparator| if comparator.starts_with(|c: char| c.is_ascii_digit()) { format!("={}", comparator) } else { comparator.to_string() })
+                .collect::<Vec<_>>()
+                .join(", ");
+            VersionReq::parse(if alternative.is_empty() { "*" } else { &alternative })
+                .map_err(|error| format!("Invalid version range \"{}\": {}", range, error))
+        }).collect::<Result<_, _>>()?;
+
+        Ok(VersionRange { alternatives })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|alternative| alternative.matches(version))
+    }
+}
+
+/// Normalizes a permission of frontwork.toml to a deno flag. Accepted forms are
+/// `read`, `read=./data`, `allow-read=./data`, `--allow-read=./data` and the same with `deny-`.
+pub fn permission_flag(permission: &str) -> Result<String, String> {
+    let permission = permission.trim().trim_start_matches("--");
+    let (name, scope) = match permission.split_once('=') {
+        Some((name, scope)) => (name, Some(scope)),
+        None => (permission, None),
+    };
+
+    let (kind, name) = if let Some(name) = name.strip_prefix("allow-") {
+        ("allow", name)
+    } else if let Some(name) = name.strip_prefix("deny-") {
+        ("deny", name)
+    } else {
+        ("allow", name)
+    };
+
+    if !DENO_PERMISSIONS.contains(&name) || (kind == "deny" && name == "all") {
+        return Err(format!("\"{}\" is not a deno permission. Possible values: {}", permission, DENO_PERMISSIONS.join(", ")));
+    }
+    if scope.is_some() && name == "all" {
+        return Err(format!("--{}-{} can not be scoped", kind, name));
+    }
+
+    Ok(match scope {
+        Some(scope) => format!("--{}-{}={}", kind, name, scope),
+        None => format!("--{}-{}", kind, name),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_executable_from() {
+        let dir = TempDir::new("deno");
+        let project_deno = dir.join("project").join(PROJECT_TOOLCHAIN_DIR).join("bin/deno");
+        let home_deno = dir.join("home/.deno/bin/deno");
+        fs::create_dir_all(project_deno.parent().unwrap()).unwrap();
+        fs::create_dir_all(home_deno.parent().unwrap()).unwrap();
+        fs::create_dir_all(dir.join("project/src/components")).unwrap();
+        fs::write(&home_deno, "").unwrap();
+
+        let deno_install = Some(dir.join("home/.deno").into_os_string());
+        assert_eq!(executable_from(&dir.join("project/src/components"), None), PathBuf::from("deno"));
+        assert_eq!(executable_from(&dir.join("project/src/components"), deno_install.clone()), home_deno);
+        fs::write(&project_deno, "").unwrap();
+        assert_eq!(executable_from(&dir.join("project/src/components"), deno_install), project_deno);
+    }
+
+    #[test]
+    fn test_version_range() {
+        let matches = |range: &str, version: &str| VersionRange::parse(range).unwrap().matches(&Version::parse(version).unwrap());
+        assert!(matches("^1.44.0", "1.46.3"));
+        assert!(!matches("^1.44.0", "2.0.0"));
+        assert!(matches(">=1.40 <3", "2.2.3"));
+        assert!(!matches(">=1.40 <3", "3.0.0"));
+        assert!(matches("1.44.0 || ^2.1", "2.4.0"));
+        assert!(!matches("1.44.0", "1.44.1"));
+        assert!(matches("*", "2.0.0"));
+        assert!(VersionRange::parse("latest").is_err());
+    }
+
+    #[test]
+    fn test_permission_flag() {
+        assert_eq!(permission_flag("read").unwrap(), "--allow-read");
+        assert_eq!(permission_flag("write=./uploads").unwrap(), "--allow-write=./uploads");
+        assert_eq!(permission_flag("--allow-read=./data,/tmp").unwrap(), "--allow-read=./data,/tmp");
+        assert_eq!(permission_flag("deny-net=example.com").unwrap(), "--deny-net=example.com");
+        assert_eq!(permission_flag("allow-ffi").unwrap(), "--allow-ffi");
+        assert!(permission_flag("--allow-everything").is_err());
+        assert!(permission_flag("--deny-all").is_err());
+        assert!(permission_flag("all=./data").is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use anyhow::{bail, Context, Result};
+use semver::Version;
+use serde::Deserialize;
+use crate::{deno::VersionRange, download::{self, DownloadOptions}};
+
+
+const RELEASES_API_URL: &str = "https://api.github.com/repos/denoland/deno/releases";
+const RELEASES_DOWNLOAD_URL: &str = "https://github.com/denoland/deno/releases/download";
+/// Only exists on mirrors: the released versions, one per line, e.g. `ls releases/download` of the mirror
+const RELEASES_INDEX_URL: &str = "https://github.com/denoland/deno/releases/versions.txt";
+/// The releases API returns at most 100 releases per page
+const RELEASES_MAX_PAGES: usize = 5;
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    prerelease: bool,
+    draft: bool,
+}
+
+/// Resolves the version requested by `frontwork install --deno-version` or `engines.deno`:
+/// "latest", an exact version like "2.1.4" or a range like "^2.1".
+/// With a mirror the versions are read from its releases/versions.txt instead of the GitHub API.
+pub async fn resolve_version(requested: &str, options: &DownloadOptions) -> Result<Version> {
+    let requested = requested.trim().trim_start_matches('v');
+    if let Ok(version) = Version::parse(requested) {
+        return Ok(version);
+    }
+
+    if options.mirror.is_some() {
+        let index = download::fetch_text(RELEASES_INDEX_URL, options).await.with_context(|| format!(
+            "The {} has no release index. Add denoland/deno/releases/versions.txt with one version per line, or install an exact version with --deno-version",
+            download::MIRROR_ENV
+        ))?;
+        let versions = index.lines().filter_map(|line| Version::parse(line.trim().trim_start_matches('v')).ok());
+        let matching = match requested {
+            "latest" => versions.filter(|version| version.pre.is_empty()).max(),
+            _ => {
+                let range = VersionRange::parse(requested).map_err(anyhow::Error::msg)?;
+                versions.filter(|version| version.pre.is_empty() && range.matches(version)).max()
+            }
+        };
+        return matching.with_context(|| format!("No deno release of the release index of {} satisfies \"{}\"", download::MIRROR_ENV, requested));
+    }
+
+    let client = download::http_client()?;
+    if requested == "latest" {
+        let release: Release = client.get(format!("{}/latest", RELEASES_API_URL)).send().await?
+            .error_for_status()?
+            .json().await
+            .context("Unable to read the latest deno release")?;
+        return release_version(&release).context("The latest deno release has an invalid tag");
+    }
+
+    let range = VersionRange::parse(requested).map_err(anyhow::Error::msg)?;
+    for page in 1..=RELEASES_MAX_PAGES {
+        let releases: Vec<Release> = client.get(RELEASES_API_URL)
+            .query(&[("per_page", "100"), ("page", &page.to_string())])
+            .send().await?
+            .error_for_status()?
+            .json().await
+            .context("Unable to read the deno releases")?;
+        if releases.is_empty() {
+            break;
+        }
+
+        // the API returns the newest releases first
+        let matching = releases.iter()
+            .filter(|release| !release.prerelease && !release.draft)
+            .filter_map(release_version)
+            .filter(|version| version.pre.is_empty() && range.matches(version))
+            .max();
+        if let Some(version) = matching {
+            return Ok(version);
+        }
+    }
+
+    bail!("No deno release satisfies \"{}\"", requested)
+}
+
+fn release_version(release: &Release) -> Option<Version> {
+    Version::parse(release.tag_name.trim_start_matches('v')).ok()
+}
+
+pub fn archive_url(version: &Version, target: &str) -> String {
+    format!("{}/v{}/deno-{}.zip", RELEASES_DOWNLOAD_URL, version, target)
+}
+
+/// Downloads the published `{archive_url}.sha256sum`
+pub async fn fetch_sha256(archive_url: &str, options: &DownloadOptions) -> Result<String> {
+    let checksum_url = format!("{}.sha256sum", archive_url);
+    let content = download::fetch_text(&checksum_url, options).await?;
+
+    download::parse_sha256sum(&content).with_context(|| format!("{} contains no SHA-256 sum", checksum_url))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+    use std::fs;
+    use url::Url;
+
+    #[tokio::test]
+    async fn test_resolve_version_from_mirror() {
+        let mirror_path = TempDir::new("deno-release");
+        let options = DownloadOptions { mirror: Some(Url::from_directory_path(&mirror_path).unwrap().to_string()), ..Default::default() };
+
+        assert_eq!(resolve_version("v2.1.4", &options).await.unwrap(), Version::new(2, 1, 4));
+        let error = resolve_version("^2.1", &options).await.unwrap_err();
+        assert!(format!("{:#}", error).contains("or install an exact version with --deno-version"), "{:#}", error);
+
+        fs::create_dir_all(mirror_path.join("denoland/deno/releases")).unwrap();
+        fs::write(mirror_path.join("denoland/deno/releases/versions.txt"), "v2.0.6\nv2.1.4\nv2.1.10\nv2.2.0-rc.1\n\nv1.46.3\n").unwrap();
+        assert_eq!(resolve_version("^2.1", &options).await.unwrap(), Version::new(2, 1, 10));
+        assert_eq!(resolve_version("latest", &options).await.unwrap(), Version::new(2, 1, 10));
+        assert!(resolve_version("^3", &options).await.is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use std::{fs::{self, File}, io::Write, os::unix::fs::PermissionsExt, path::{Path, PathBuf}};
+use regex::Regex;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+
+static LAUNCHER_SH_STR: &str = include_str!("../desktop/launcher.sh");
+pub const LAUNCHER_FILENAME: &str = "launch.sh";
+const DEFAULT_PORT: &str = "8080";
+
+/// Writes the webview launcher script next to the compiled main.service and bundles both
+/// together with the client files into `{dist_path}.zip`. Returns the path of the archive.
+pub fn package_desktop(dist_path: &Path, environment_file_path: &Path, app_name: &str) -> Result<PathBuf, String> {
+    let environment_file = fs::read_to_string(environment_file_path)
+        .map_err(|error| format!("Unable to read {}: {}", environment_file_path.display(), error))?;
+    let launcher = LAUNCHER_SH_STR
+        .replace("{{PORT}}", &read_port(&environment_file))
+        .replace("{{APP_NAME}}", app_name);
+
+    let launcher_path = dist_path.join(LAUNCHER_FILENAME);
+    fs::write(&launcher_path, launcher).map_err(|error| format!("Unable to write {}: {}", launcher_path.display(), error))?;
+    crate::utils::make_file_executable(launcher_path.to_str().unwrap()).map_err(|error| error.to_string())?;
+
+    let archive_path = dist_path.with_extension("zip");
+    let mut archive = ZipWriter::new(File::create(&archive_path).map_err(|error| error.to_string())?);
+    add_directory_to_zip(&mut archive, dist_path, dist_path).map_err(|error| error.to_string())?;
+    archive.finish().map_err(|error| error.to_string())?;
+
+    Ok(archive_path)
+}
+
+/// The `port` of APP_CONFIG in the environment file
+fn read_port(environment_file: &str) -> String {
+    let pattern = Regex::new(r"\bport\s*:\s*(\d+)").unwrap();
+    pattern.captures(environment_file)
+        .map(|captures| captures[1].to_string())
+        .unwrap_or(DEFAULT_PORT.to_string())
+}
+
+fn add_directory_to_zip(archive: &mut ZipWriter<File>, base_path: &Path, dir_path: &Path) -> zip::result::ZipResult<()> {
+    let prefix = base_path.file_name().unwrap().to_string_lossy().to_string();
+
+    for entry in fs::read_dir(dir_path)? {
+        let path = entry?.path();
+        let name = format!("{}/{}", prefix, path.strip_prefix(base_path).unwrap().to_string_lossy());
+
+        if path.is_dir() {
+            archive.add_directory(&name, SimpleFileOptions::default())?;
+            add_directory_to_zip(archive, base_path, &path)?;
+        } else {
+            let mode = fs::metadata(&path)?.permissions().mode();
+            archive.start_file(&name, SimpleFileOptions::default().unix_permissions(mode))?;
+            archive.write_all(&fs::read(&path)?)?;
+        }
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_port() {
+        assert_eq!(read_port("export const APP_CONFIG = {\n\tstage: EnvironmentStage.Production,\n\tport: 9090,\n};"), "9090");
+        assert_eq!(read_port("export const APP_CONFIG = {};"), DEFAULT_PORT);
+    }
+}
@@ -0,0 +1,189 @@
+use std::{fs, path::Path};
+use regex::Regex;
+use semver::Version;
+use serde::Serialize;
+use crate::{config::FrontworkConfig, deno, environment_overlay::environment_file_path, package_json::PackageJson};
+use crate::environment_platform::{Environment, Platform};
+
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+    /// Not checked, because a check it depends on failed
+    Skipped,
+}
+
+#[derive(Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// How to fix the problem; None if the check passed
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, message: String) -> Check {
+        Check { name, status: CheckStatus::Ok, message, hint: None }
+    }
+
+    fn failed(name: &'static str, status: CheckStatus, message: String, hint: impl Into<String>) -> Check {
+        Check { name, status, message, hint: Some(hint.into()) }
+    }
+
+    fn skipped(name: &'static str, message: impl Into<String>) -> Check {
+        Check { name, status: CheckStatus::Skipped, message: message.into(), hint: None }
+    }
+}
+
+/// Checks whether this machine is ready to develop the frontwork project in `project_path`
+pub fn run_checks(project_path: &str) -> Vec<Check> {
+    let deno_version = deno::version();
+    let project = FrontworkConfig::from_project_directory(project_path);
+
+    vec![
+        check_deno(deno_version.as_deref()),
+        check_engines(project_path, deno_version.as_deref()),
+        check_project(&project),
+        check_std_version(project.as_ref().ok()),
+        check_environment_files(project.as_ref().ok()),
+    ]
+}
+
+pub fn print_checks(checks: &[Check]) {
+    for check in checks {
+        let status = match check.status {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Warning => "warning",
+            CheckStatus::Error => "ERROR",
+            CheckStatus::Skipped => "skipped",
+        };
+        println!("{:<8} {:<18} {}", status, check.name, check.message);
+        if let Some(hint) = &check.hint {
+            println!("{:<8} {:<18} fix: {}", "", "", hint);
+        }
+    }
+}
+
+fn check_deno(deno_version: Option<&str>) -> Check {
+    match deno_version {
+        Some(version) => Check::ok("deno", format!("deno {} is installed ({})", version, deno::executable().display())),
+        None => Check::failed("deno", CheckStatus::Error, format!("deno was not found in {}, $DENO_INSTALL or PATH", deno::PROJECT_TOOLCHAIN_DIR), "Run 'frontwork install' and restart the shell"),
+    }
+}
+
+fn check_engines(project_path: &str, deno_version: Option<&str>) -> Check {
+    const NAME: &str = "engines.deno";
+    let package_json = match PackageJson::read(project_path) {
+        Ok(package_json) => package_json,
+        Err(error) => return Check::skipped(NAME, error),
+    };
+    let Some(range) = package_json.engines.get("deno") else {
+        return Check::failed(NAME, CheckStatus::Warning, "package.json does not specify engines.deno".to_string(), "Add the supported deno versions to package.json, e.g. \"engines\": { \"deno\": \"^2.0.0\" }");
+    };
+    let Some(deno_version) = deno_version else {
+        return Check::skipped(NAME, "deno is not installed");
+    };
+
+    let range_matches = deno::VersionRange::parse(range).and_then(|version_range| {
+        let version = Version::parse(deno_version).map_err(|error| format!("Invalid deno version \"{}\": {}", deno_version, error))?;
+        Ok(version_range.matches(&version))
+    });
+    match range_matches {
+        Ok(true) => Check::ok(NAME, format!("deno {} satisfies \"{}\"", deno_version, range)),
+        Ok(false) => Check::failed(NAME, CheckStatus::Error, format!("deno {} does not satisfy \"{}\"", deno_version, range), "Run 'frontwork install' to install a matching deno version"),
+        Err(error) => Check::failed(NAME, CheckStatus::Error, error, "Fix engines.deno in package.json"),
+    }
+}
+
+fn check_project(project: &Result<FrontworkConfig, String>) -> Check {
+    match project {
+        Ok(config) => Check::ok("project", format!("{} is a frontwork project", config.project_path)),
+        Err(error) => Check::failed("project", CheckStatus::Error, error.clone(), "Change to the project directory or run 'frontwork init' to initialize the project"),
+    }
+}
+
+fn check_std_version(config: Option<&FrontworkConfig>) -> Check {
+    const NAME: &str = "frontwork-std";
+    let Some(config) = config else {
+        return Check::skipped(NAME, "not a frontwork project");
+    };
+
+    let dependencies_path = format!("{}/dependencies.ts", config.src_path());
+    let Ok(dependencies) = fs::read_to_string(&dependencies_path) else {
+        return Check::failed(NAME, CheckStatus::Error, format!("Can not open {}", dependencies_path), "Restore dependencies.ts from a new project created by 'frontwork new'");
+    };
+    let pattern = Regex::new(r"https://deno\.land/x/frontwork@([0-9]+\.[0-9]+\.[0-9]+)/").unwrap();
+    let Some(captures) = pattern.captures(&dependencies) else {
+        return Check::failed(NAME, CheckStatus::Warning, format!("{} does not import a pinned version of frontwork", dependencies_path), "Import frontwork from https://deno.land/x/frontwork@{version}/lib.ts");
+    };
+
+    let cli_version = env!("CARGO_PKG_VERSION");
+    if &captures[1] == cli_version {
+        Check::ok(NAME, format!("dependencies.ts uses frontwork {}", cli_version))
+    } else {
+        Check::failed(NAME, CheckStatus::Warning, format!("dependencies.ts uses frontwork {}, but this CLI is {}", &captures[1], cli_version), "Run 'frontwork update'")
+    }
+}
+
+/// environment.ts and the files of production and staging on the web are always required.
+/// The other platforms are checked once one of their environment files exists.
+fn check_environment_files(config: Option<&FrontworkConfig>) -> Check {
+    const NAME: &str = "environments";
+    let Some(config) = config else {
+        return Check::skipped(NAME, "not a frontwork project");
+    };
+
+    let environments_path = config.path(&config.build.environments_dir);
+    let environments_path = Path::new(&environments_path);
+    let environments = [Environment::Development, Environment::Staging, Environment::Production];
+    let mut missing = Vec::new();
+
+    for platform in [Platform::Web, Platform::Desktop, Platform::Android] {
+        let paths: Vec<_> = environments.iter().map(|environment| environment_file_path(environments_path, environment, &platform)).collect();
+        if platform != Platform::Web && !paths.iter().any(|path| path.exists()) {
+            continue;
+        }
+        missing.extend(paths.into_iter().filter(|path| !path.exists()));
+    }
+
+    if missing.is_empty() {
+        Check::ok(NAME, "all environment files exist".to_string())
+    } else {
+        let missing = missing.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(", ");
+        Check::failed(NAME, CheckStatus::Error, format!("missing {}", missing), "Copy environment.ts to the missing files and adjust APP_CONFIG")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_project_checks() {
+        let project_path = TempDir::new("doctor");
+        let project_path_str = project_path.to_str().unwrap();
+        fs::create_dir_all(project_path.join("src/components")).unwrap();
+        fs::create_dir_all(project_path.join("src/environments")).unwrap();
+        fs::write(project_path.join("package.json"), r#"{ "scripts": {}, "engines": { "deno": "^2.0.0" } }"#).unwrap();
+        fs::write(project_path.join("src/dependencies.ts"), "export * from \"https://deno.land/x/frontwork@0.0.1/lib.ts\";").unwrap();
+        for file in ["environment.ts", "environment.staging.web.ts", "environment.production.web.ts", "environment.production.desktop.ts"] {
+            fs::write(project_path.join("src/environments").join(file), "").unwrap();
+        }
+
+        let checks = run_checks(project_path_str);
+        assert_eq!(check_engines(project_path_str, Some("2.2.3")).status, CheckStatus::Ok);
+        assert_eq!(check_engines(project_path_str, Some("1.46.3")).status, CheckStatus::Error);
+
+        assert_eq!(checks[2].status, CheckStatus::Ok);
+        assert_eq!(checks[3].status, CheckStatus::Warning);
+        assert_eq!(checks[4].status, CheckStatus::Error);
+        assert!(checks[4].message.contains("environment.development.desktop.ts"));
+        assert!(!checks[4].message.contains("android"));
+    }
+}
@@ -0,0 +1,186 @@
+use std::{fs, path::{Path, PathBuf}};
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use url::Url;
+use crate::environment_platform::{Environment, Platform};
+
+
+/// Redirects `src/environments/environment.ts` to `environment.{environment}.{platform}.ts` through an import map,
+/// so that building for another environment never touches the files in the source tree.
+/// The import map is written to the temporary directory and deleted when the overlay is dropped.
+pub struct EnvironmentOverlay {
+    import_map_path: PathBuf,
+    /// Written by frontwork-bundler.ts once it applied the import map
+    receipt_path: PathBuf,
+}
+
+impl EnvironmentOverlay {
+    /// Returns None for development on the web, because environment.ts is already the file for it.
+    pub fn new(environments_path: &Path, deno_config_path: &Path, environment: &Environment, platform: &Platform) -> Result<Option<Self>> {
+        let envfile_dev_path = environment_file_path(environments_path, &Environment::Development, &Platform::Web);
+        let envfile_selected_path = environment_file_path(environments_path, environment, platform);
+        if envfile_selected_path == envfile_dev_path {
+            return Ok(None);
+        }
+        if !envfile_selected_path.exists() {
+            bail!("environment file ({}) does not exists", envfile_selected_path.display());
+        }
+
+        let mut imports = project_imports(deno_config_path)?;
+        imports.insert(file_url(&envfile_dev_path)?, Value::String(file_url(&envfile_selected_path)?));
+
+        let import_map = serde_json::json!({ "imports": imports });
+        let import_map_path = std::env::temp_dir().join(format!("frontwork-import-map-{}.json", std::process::id()));
+        fs::write(&import_map_path, serde_json::to_string_pretty(&import_map)?)
+            .with_context(|| format!("Unable to write import map {}", import_map_path.display()))?;
+
+        let receipt_path = import_map_path.with_extension("applied");
+        fs::remove_file(&receipt_path).ok();
+
+        Ok(Some(Self { import_map_path, receipt_path }))
+    }
+
+    pub fn import_map_path(&self) -> &Path {
+        &self.import_map_path
+    }
+
+    pub fn import_map_url(&self) -> String {
+        Url::from_file_path(&self.import_map_path).unwrap().to_string()
+    }
+
+    pub fn receipt_path(&self) -> &Path {
+        &self.receipt_path
+    }
+
+    /// Whether the client bundler confirmed that it applied the import map
+    pub fn is_applied_by_bundler(&self) -> bool {
+        self.receipt_path.exists()
+    }
+}
+
+impl Drop for EnvironmentOverlay {
+    fn drop(&mut self) {
+        fs::remove_file(&self.import_map_path).ok();
+        fs::remove_file(&self.receipt_path).ok();
+    }
+}
+
+/// `environment.{environment}.{platform}.ts`; environment.ts for development on the web
+pub fn environment_file_path(environments_path: &Path, environment: &Environment, platform: &Platform) -> PathBuf {
+    if *environment == Environment::Development && *platform == Platform::Web {
+        environments_path.join("environment.ts")
+    } else {
+        environments_path.join(format!("environment.{}.{}.ts", environment.to_str_lcase(), platform.to_str_lcase()))
+    }
+}
+
+fn file_url(path: &Path) -> Result<String> {
+    let absolute_path = path.canonicalize().with_context(|| format!("Unable to resolve {}", path.display()))?;
+    Ok(Url::from_file_path(absolute_path).unwrap().to_string())
+}
+
+/// The "imports" of deno.jsonc. They have to be kept, because --import-map replaces them.
+/// Relative targets are resolved against the directory of deno.jsonc, since the import map lives elsewhere.
+fn project_imports(config_path: &Path) -> Result<Map<String, Value>> {
+    if !config_path.exists() {
+        return Ok(Map::new());
+    }
+
+    let content = fs::read_to_string(config_path)?;
+    let config: Value = serde_json::from_str(&strip_jsonc_comments(&content))
+        .with_context(|| format!("Unable to parse {}", config_path.display()))?;
+    let Some(Value::Object(imports)) = config.get("imports") else {
+        return Ok(Map::new());
+    };
+
+    let config_dir = config_path.parent().unwrap_or(Path::new("."));
+    let project_url = Url::from_directory_path(config_dir.canonicalize()?).unwrap();
+    Ok(imports.iter().map(|(key, value)| {
+        let value = match value.as_str() {
+            Some(target) if target.starts_with("./") || target.starts_with("../") => {
+                Value::String(project_url.join(target).map(|url| url.to_string()).unwrap_or(target.to_string()))
+            }
+            _ => value.clone(),
+        };
+        (key.clone(), value)
+    }).collect())
+}
+
+/// Removes `//` and `/* */` comments outside of strings
+fn strip_jsonc_comments(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            result.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    result.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            result.push(c);
+        } else if c == '/' && chars.peek() == Some(&'/') {
+            for c in chars.by_ref() {
+                if c == '\n' {
+                    result.push('\n');
+                    break;
+                }
+            }
+        } else if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            let mut previous = ' ';
+            for c in chars.by_ref() {
+                if previous == '*' && c == '/' {
+                    break;
+                }
+                previous = c;
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_import_map_redirects_environment_file() {
+        let project_path = TempDir::new("overlay");
+        fs::create_dir_all(project_path.join("src/environments")).unwrap();
+        fs::write(project_path.join("src/environments/environment.ts"), "").unwrap();
+        fs::write(project_path.join("src/environments/environment.staging.web.ts"), "").unwrap();
+        fs::write(project_path.join("deno.jsonc"), "{\n  // comment\n  \"imports\": { \"lib/\": \"./lib/\", \"std\": \"https://deno.land/std\" } /* end */\n}").unwrap();
+
+        let environments_path = project_path.join("src/environments");
+        let deno_config_path = project_path.join("deno.jsonc");
+        assert!(EnvironmentOverlay::new(&environments_path, &deno_config_path, &Environment::Development, &Platform::Web).unwrap().is_none());
+        assert!(EnvironmentOverlay::new(&environments_path, &deno_config_path, &Environment::Production, &Platform::Web).is_err());
+        assert!(EnvironmentOverlay::new(&environments_path, &deno_config_path, &Environment::Development, &Platform::Desktop).is_err());
+
+        let overlay = EnvironmentOverlay::new(&environments_path, &deno_config_path, &Environment::Staging, &Platform::Web).unwrap().unwrap();
+        let import_map: Value = serde_json::from_str(&fs::read_to_string(overlay.import_map_path()).unwrap()).unwrap();
+        let imports = import_map["imports"].as_object().unwrap();
+        let environments_url = Url::from_directory_path(project_path.join("src/environments").canonicalize().unwrap()).unwrap();
+        assert_eq!(imports[&environments_url.join("environment.ts").unwrap().to_string()], environments_url.join("environment.staging.web.ts").unwrap().to_string());
+        assert_eq!(imports["std"], "https://deno.land/std");
+        assert!(imports["lib/"].as_str().unwrap().starts_with("file:///"));
+
+        // the source tree is untouched and the import map is removed afterwards
+        let import_map_path = overlay.import_map_path().to_path_buf();
+        drop(overlay);
+        assert!(!import_map_path.exists());
+        assert!(project_path.join("src/environments/environment.staging.web.ts").exists());
+    }
+}
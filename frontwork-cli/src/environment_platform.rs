@@ -24,3 +24,29 @@ impl Environment {
     }
 }
 
+
+/// Mirrors `EnvironmentPlatform` of frontwork-std
+#[derive(PartialEq, Clone, clap::ValueEnum)]
+pub enum Platform {
+    Web,
+    Desktop,
+    Android,
+}
+
+impl Platform {
+    pub fn to_str(&self) -> &str {
+        match self {
+            Platform::Web => "Web",
+            Platform::Desktop => "Desktop",
+            Platform::Android => "Android",
+        }
+    }
+
+    pub fn to_str_lcase(&self) -> &str {
+        match self {
+            Platform::Web => "web",
+            Platform::Desktop => "desktop",
+            Platform::Android => "android",
+        }
+    }
+}
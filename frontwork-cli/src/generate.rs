@@ -0,0 +1,248 @@
+use std::path::{Path, PathBuf};
+use convert_case::{Case, Casing};
+use regex::Regex;
+use crate::component;
+use crate::config::FrontworkConfig;
+use crate::scaffold::{self, read_file, relative_import_path, write_file};
+use crate::typescript::{self, apply_edits, line_indent, line_start, TokenKind, Tokens};
+
+
+/// Creates src/subservices/{name}.ts and adds it to the FrontworkWebservice of the service entry. Returns the touched files.
+pub fn subservice(config: &FrontworkConfig, name: &str, template: Option<&str>) -> Result<Vec<PathBuf>, String> {
+    let snake_name = parse_name(name, "_subservice")?;
+    let export_name = format!("{}_subservice", snake_name);
+    let directory = Path::new(&config.src_path()).join("subservices");
+    let file = directory.join(format!("{}.ts", snake_name));
+
+    let service_entry_path = PathBuf::from(config.path(&config.build.service_entry));
+    let service_entry_directory = service_entry_path.parent().unwrap();
+    let service_entry = read_file(&service_entry_path)?;
+    let tokens = Tokens::new(&service_entry)?;
+
+    // frontwork-service.ts from where the service entry imports FrontworkWebservice
+    let service_module = tokens.imports().into_iter()
+        .find(|import| import.specifiers.iter().any(|specifier| specifier.local == "FrontworkWebservice"))
+        .map(|import| match import.module.starts_with('.') {
+            true => relative_import_path(&directory, &service_entry_directory.join(&import.module)),
+            false => import.module,
+        })
+        .unwrap_or_else(|| format!("https://deno.land/x/frontwork@{}/frontwork-service.ts", env!("CARGO_PKG_VERSION")));
+
+    let placeholders = [
+        ("snake_name", snake_name.clone()),
+        ("export_name", export_name.clone()),
+        ("dependencies_path", relative_import_path(&directory, &Path::new(&config.src_path()).join("dependencies.ts"))),
+        ("service_module", service_module),
+    ];
+    let files = scaffold::render_template(config, "subservice", template, &directory, &placeholders)?;
+    if !files.iter().any(|(path, _)| *path == file) {
+        return Err(format!("The subservice template has no {{{{snake_name}}}}.ts, which would become {}", file.display()));
+    }
+
+    // `new FrontworkWebservice(...).add_subservice(name).start()`
+    let start = (1..tokens.tokens.len()).rev()
+        .find(|&index| tokens.is_word(index, "start") && tokens.text(index - 1) == "." && tokens.text(index + 1) == "(")
+        .ok_or_else(|| format!("{} does not call .start() of FrontworkWebservice. Please add .add_subservice({}) yourself.", config.build.service_entry, export_name))?;
+    let dot = tokens.tokens[start - 1].start;
+    let call = if service_entry[line_start(&service_entry, dot)..dot].trim().is_empty() {
+        format!(".add_subservice({})\n{}", export_name, line_indent(&service_entry, dot))
+    } else {
+        format!(".add_subservice({})", export_name)
+    };
+
+    let import_path = relative_import_path(service_entry_directory, &file);
+    let import_statement = format!("import {{ {} }} from \"{}\";", export_name, import_path);
+    let mut edits = vec![(dot..dot, call)];
+    edits.extend(typescript::import_edit(&service_entry, &tokens, &export_name, &import_path, &import_statement)
+        .map_err(|error| format!("{}: {}", config.build.service_entry, error))?);
+
+    scaffold::write_files(&files)?;
+    write_file(&service_entry_path, &apply_edits(&service_entry, edits))?;
+
+    Ok(files.into_iter().map(|(path, _)| path).chain([service_entry_path]).collect())
+}
+
+/// Creates a BeforeRouteEvent in src/middlewares/{name}.ts and chains it into before_route of the FrontworkMiddleware
+/// in routes.ts with chain_before_route. Returns the touched files.
+pub fn middleware(config: &FrontworkConfig, name: &str, template: Option<&str>) -> Result<Vec<PathBuf>, String> {
+    let snake_name = parse_name(name, "_middleware")?;
+    let export_name = format!("{}_middleware", snake_name);
+    let directory = Path::new(&config.src_path()).join("middlewares");
+    let file = directory.join(format!("{}.ts", snake_name));
+
+    let routes_file_path = component::routes_file_path(config);
+    let routes = read_file(&routes_file_path)?;
+    let tokens = Tokens::new(&routes)?;
+
+    let placeholders = [
+        ("snake_name", snake_name.clone()),
+        ("export_name", export_name.clone()),
+        ("dependencies_path", relative_import_path(&directory, &Path::new(&config.src_path()).join("dependencies.ts"))),
+    ];
+    let files = scaffold::render_template(config, "middleware", template, &directory, &placeholders)?;
+    if !files.iter().any(|(path, _)| *path == file) {
+        return Err(format!("The middleware template has no {{{{snake_name}}}}.ts, which would become {}", file.display()));
+    }
+
+    // `new FrontworkMiddleware({ before_route: ..., ... })`
+    let open = (2..tokens.tokens.len())
+        .find(|&index| tokens.is_word(index - 2, "new") && tokens.is_word(index - 1, "FrontworkMiddleware") && tokens.text(index) == "(" && tokens.text(index + 1) == "{")
+        .map(|index| index + 1)
+        .ok_or_else(|| format!("routes.ts does not create a FrontworkMiddleware. Please add {} to its before_route yourself.", export_name))?;
+    let close = tokens.closing_bracket(open).ok_or("routes.ts: The FrontworkMiddleware is not closed")?;
+    let before_route = tokens.array_elements(open, close).into_iter()
+        .find(|property| tokens.is_word(property.first_token, "before_route") && tokens.text(property.first_token + 1) == ":")
+        .ok_or_else(|| format!("The FrontworkMiddleware in routes.ts has no before_route. Please add {} yourself.", export_name))?;
+
+    // before_route: event  =>  before_route: chain_before_route(event, name_middleware)
+    let value = before_route.first_token + 2;
+    let value_end = tokens.tokens[before_route.last_token].end;
+    let mut edits = match tokens.is_word(value, "chain_before_route") && tokens.text(value + 1) == "(" {
+        true => {
+            let close_paren = tokens.closing_bracket(value + 1).ok_or("routes.ts: chain_before_route is not closed")?;
+            let last_argument = tokens.array_elements(value + 1, close_paren).last().map_or(value + 1, |argument| argument.last_token);
+            let position = tokens.tokens[last_argument].end;
+            vec![(position..position, format!(", {}", export_name))]
+        }
+        false => vec![
+            (tokens.tokens[value].start..tokens.tokens[value].start, "chain_before_route(".to_string()),
+            (value_end..value_end, format!(", {})", export_name)),
+        ],
+    };
+
+    // chain_before_route comes from where routes.ts imports FrontworkMiddleware
+    let routes_directory = routes_file_path.parent().unwrap();
+    let dependencies_module = tokens.imports().into_iter()
+        .find(|import| import.specifiers.iter().any(|specifier| specifier.local == "FrontworkMiddleware"))
+        .map(|import| import.module)
+        .unwrap_or_else(|| relative_import_path(routes_directory, &Path::new(&config.src_path()).join("dependencies.ts")));
+    let import_path = relative_import_path(routes_directory, &file);
+    let imports = [
+        ("chain_before_route", dependencies_module.clone(), format!("import {{ chain_before_route }} from \"{}\";", dependencies_module)),
+        (export_name.as_str(), import_path.clone(), format!("import {{ {} }} from \"{}\";", export_name, import_path)),
+    ];
+    for (local, module, statement) in imports {
+        edits.extend(typescript::import_edit(&routes, &tokens, local, &module, &statement)
+            .map_err(|error| format!("routes.ts: {}", error))?);
+    }
+
+    scaffold::write_files(&files)?;
+    write_file(&routes_file_path, &apply_edits(&routes, edits))?;
+
+    Ok(files.into_iter().map(|(path, _)| path).chain([routes_file_path]).collect())
+}
+
+/// Creates a DocumentBuilder subclass in src/document_builders/{name}.ts. Returns the created files.
+pub fn document_builder(config: &FrontworkConfig, name: &str, template: Option<&str>) -> Result<Vec<PathBuf>, String> {
+    let snake_name = parse_name(name, "_document_builder")?;
+    let directory = Path::new(&config.src_path()).join("document_builders");
+
+    let placeholders = [
+        ("snake_name", snake_name.clone()),
+        ("class_name", format!("{}DocumentBuilder", snake_name.to_case(Case::UpperCamel))),
+        ("dependencies_path", relative_import_path(&directory, &Path::new(&config.src_path()).join("dependencies.ts"))),
+    ];
+    let files = scaffold::render_template(config, "document_builder", template, &directory, &placeholders)?;
+    scaffold::write_files(&files)?;
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Creates src/i18n/{locale}.json with the translations of the first locale to translate them and
+/// registers it in the i18n array of src/i18n.ts. Returns the touched files.
+pub fn locale(config: &FrontworkConfig, locale: &str) -> Result<Vec<PathBuf>, String> {
+    if !Regex::new(r"^[a-z]{2,3}(-[A-Za-z0-9]{2,8})*$").unwrap().is_match(locale) {
+        return Err(format!("Invalid locale \"{}\". Use codes like fr or pt-BR.", locale));
+    }
+
+    let src_path = PathBuf::from(config.src_path());
+    let i18n_path = src_path.join("i18n.ts");
+    let file = src_path.join("i18n").join(format!("{}.json", locale));
+    let i18n = read_file(&i18n_path)?;
+    let tokens = Tokens::new(&i18n)?;
+
+    let (open, close) = tokens.array("i18n").ok_or("src/i18n.ts does not contain the i18n array")?;
+    for element in tokens.array_elements(open, close) {
+        let first_token = element.first_token;
+        let registered = tokens.is_word(first_token, "new") && tokens.is_word(first_token + 1, "I18nLocale")
+            && tokens.tokens.get(first_token + 3).is_some_and(|token| token.kind == TokenKind::String)
+            && tokens.text(first_token + 3).trim_matches(['"', '\'', '`']) == locale;
+        if registered {
+            return Err(format!("The locale \"{}\" is already registered in src/i18n.ts", locale));
+        }
+    }
+
+    // the keys of the first locale, e.g. english.json
+    let content = tokens.imports().into_iter()
+        .find(|import| import.module.ends_with(".json"))
+        .and_then(|import| read_file(&src_path.join(&import.module)).ok())
+        .unwrap_or("{\n}\n".to_string());
+
+    // prefixed, because codes like "in" or "do" are reserved words
+    let identifier = format!("locale_{}", locale.to_case(Case::Snake));
+    let import_path = relative_import_path(&src_path, &file);
+    let import_statement = format!("import {{ default as {} }} from \"{}\" with {{ type: \"json\" }};", identifier, import_path);
+    let mut edits = vec![typescript::append_element_edit(&i18n, &tokens, open, close, &format!("new I18nLocale(\"{}\", {} as I18nLocaleTranslation)", locale, identifier))];
+    edits.extend(typescript::import_edit(&i18n, &tokens, &identifier, &import_path, &import_statement)
+        .map_err(|error| format!("src/i18n.ts: {}", error))?);
+
+    scaffold::write_files(&[(file.clone(), content)])?;
+    write_file(&i18n_path, &apply_edits(&i18n, edits))?;
+
+    Ok(vec![file, i18n_path])
+}
+
+/// The name in snake_case without the `suffix` of the kind, e.g. "AuthSubservice" is "auth"
+fn parse_name(name: &str, suffix: &str) -> Result<String, String> {
+    let snake_name = name.trim().to_case(Case::Snake);
+    let snake_name = snake_name.strip_suffix(suffix).unwrap_or(&snake_name);
+    if snake_name.is_empty() || !snake_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("Invalid name \"{}\"", name));
+    }
+    Ok(snake_name.to_string())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_generate() {
+        let project_path = TempDir::new("generate");
+        let config = FrontworkConfig { project_path: project_path.to_string_lossy().to_string(), ..Default::default() };
+        fs::create_dir_all(project_path.join("src/i18n")).unwrap();
+        fs::write(project_path.join("src/main.service.ts"), "import { FrontworkWebservice } from \"https://deno.land/x/frontwork@0.4.4/frontwork-service.ts\";\n\nnew FrontworkWebservice(APP_CONFIG)\n    .setup_assets_resolver(__dir + '/assets')\n    .start();").unwrap();
+        fs::write(project_path.join("src/i18n.ts"), "import { default as english } from \"./i18n/english.json\" with { type: \"json\" };\n\nexport const i18n: I18n = [\n    new I18nLocale(\"en\", english as I18nLocaleTranslation),\n];").unwrap();
+        fs::write(project_path.join("src/i18n/english.json"), "{ \"a-home\": \"Startpage\" }").unwrap();
+
+        subservice(&config, "AuthSubservice", None).unwrap();
+        let service_entry = fs::read_to_string(project_path.join("src/main.service.ts")).unwrap();
+        assert!(service_entry.starts_with("import { FrontworkWebservice } from \"https://deno.land/x/frontwork@0.4.4/frontwork-service.ts\";\nimport { auth_subservice } from \"./subservices/auth.ts\";\n"));
+        assert!(service_entry.ends_with("    .setup_assets_resolver(__dir + '/assets')\n    .add_subservice(auth_subservice)\n    .start();"));
+        let auth = fs::read_to_string(project_path.join("src/subservices/auth.ts")).unwrap();
+        assert!(auth.contains("import { FrontworkSubservice } from \"https://deno.land/x/frontwork@0.4.4/frontwork-service.ts\";"));
+        assert!(subservice(&config, "auth", None).unwrap_err().ends_with("auth.ts already exists"));
+
+        fs::create_dir_all(project_path.join("src/components")).unwrap();
+        fs::write(project_path.join("src/components/routes.ts"), "import { Route, FrontworkMiddleware } from \"../dependencies.ts\";\n\nexport const middleware = new FrontworkMiddleware({\n\tbefore_route: {\n\t\tbuild: async () => {},\n\t\tdom_ready: async () => {}\n\t},\n\tnot_found_handler: NotFoundComponent,\n});\n").unwrap();
+        middleware(&config, "auth", None).unwrap();
+        middleware(&config, "TrackingMiddleware", None).unwrap();
+        let routes = fs::read_to_string(project_path.join("src/components/routes.ts")).unwrap();
+        assert_eq!(routes, "import { Route, FrontworkMiddleware, chain_before_route } from \"../dependencies.ts\";\nimport { auth_middleware } from \"../middlewares/auth.ts\";\nimport { tracking_middleware } from \"../middlewares/tracking.ts\";\n\nexport const middleware = new FrontworkMiddleware({\n\tbefore_route: chain_before_route({\n\t\tbuild: async () => {},\n\t\tdom_ready: async () => {}\n\t}, auth_middleware, tracking_middleware),\n\tnot_found_handler: NotFoundComponent,\n});\n");
+        assert!(fs::read_to_string(project_path.join("src/middlewares/auth.ts")).unwrap().contains("export const auth_middleware: BeforeRouteEvent = {"));
+
+        locale(&config, "pt-BR").unwrap();
+        let i18n = fs::read_to_string(project_path.join("src/i18n.ts")).unwrap();
+        assert!(i18n.contains("import { default as locale_pt_br } from \"./i18n/pt-BR.json\" with { type: \"json\" };\n"));
+        assert!(i18n.ends_with("    new I18nLocale(\"en\", english as I18nLocaleTranslation),\n    new I18nLocale(\"pt-BR\", locale_pt_br as I18nLocaleTranslation),\n];"));
+        locale(&config, "in").unwrap();
+        let i18n = fs::read_to_string(project_path.join("src/i18n.ts")).unwrap();
+        assert!(i18n.contains("import { default as locale_in } from \"./i18n/in.json\" with { type: \"json\" };\n"));
+        assert!(i18n.contains("    new I18nLocale(\"in\", locale_in as I18nLocaleTranslation),\n"));
+        assert_eq!(fs::read_to_string(project_path.join("src/i18n/pt-BR.json")).unwrap(), "{ \"a-home\": \"Startpage\" }");
+        assert_eq!(locale(&config, "en").unwrap_err(), "The locale \"en\" is already registered in src/i18n.ts");
+    }
+}
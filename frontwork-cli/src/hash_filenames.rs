@@ -0,0 +1,147 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+use regex::{Captures, Regex};
+use sha2::{Digest, Sha256};
+
+
+/// Maps the original URL of every fingerprinted file to its hashed URL. Read but not served by FrontworkWebservice.
+pub const HASHED_FILENAMES_FILENAME: &str = "hashed-filenames.json";
+const STYLE_CSS_URL: &str = "/css/style.css";
+const MAIN_CLIENT_JS_URL: &str = "/js/main.client.js";
+
+/// Renames the assets, style.css and main.client.js in the dist folder to `{name}.{hash}.{extension}`,
+/// rewrites the asset urls in style.css and writes the mapping to hashed-filenames.json.
+/// Code splitting chunks are left alone, because esbuild already names them by their hash.
+pub fn hash_filenames(dist_path: &Path) -> io::Result<BTreeMap<String, String>> {
+    let mut hashed_filenames = BTreeMap::new();
+
+    // assets first, because style.css references them
+    let assets_path = dist_path.join("assets");
+    if assets_path.exists() {
+        hash_directory(&assets_path, "/", &mut hashed_filenames)?;
+    }
+
+    let style_css_path = dist_path.join("css/style.css");
+    if style_css_path.exists() {
+        let css = fs::read_to_string(&style_css_path)?;
+        fs::write(&style_css_path, rewrite_css_urls(&css, &hashed_filenames))?;
+        let hashed_name = hash_file(&style_css_path)?;
+        hashed_filenames.insert(STYLE_CSS_URL.to_string(), format!("/css/{}", hashed_name));
+    }
+
+    let main_client_js_path = dist_path.join("js/main.client.js");
+    if main_client_js_path.exists() {
+        let hashed_name = hash_file(&main_client_js_path)?;
+        hashed_filenames.insert(MAIN_CLIENT_JS_URL.to_string(), format!("/js/{}", hashed_name));
+    }
+
+    fs::write(dist_path.join(HASHED_FILENAMES_FILENAME), serde_json::to_string_pretty(&hashed_filenames)?)?;
+    Ok(hashed_filenames)
+}
+
+/// Deletes the hashed files and the mapping of a previous build, so that neither gets hashed twice or served stale.
+pub fn remove_previous_hashed_files(dist_path: &Path) -> io::Result<()> {
+    let mapping_path = dist_path.join(HASHED_FILENAMES_FILENAME);
+    if !mapping_path.exists() {
+        return Ok(());
+    }
+
+    let hashed_filenames: BTreeMap<String, String> = serde_json::from_str(&fs::read_to_string(&mapping_path)?)?;
+    for (url, hashed_url) in hashed_filenames {
+        let file_path = if url == STYLE_CSS_URL || url == MAIN_CLIENT_JS_URL {
+            dist_path.join(&hashed_url[1..])
+        } else {
+            dist_path.join("assets").join(&hashed_url[1..])
+        };
+        fs::remove_file(file_path).ok();
+    }
+
+    fs::remove_file(mapping_path)
+}
+
+fn hash_directory(dir_path: &Path, url_prefix: &str, hashed_filenames: &mut BTreeMap<String, String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir_path)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+        if path.is_dir() {
+            hash_directory(&path, &format!("{}{}/", url_prefix, name), hashed_filenames)?;
+        } else {
+            let hashed_name = hash_file(&path)?;
+            hashed_filenames.insert(format!("{}{}", url_prefix, name), format!("{}{}", url_prefix, hashed_name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Renames the file to `{name}.{hash}.{extension}` and returns the new filename
+fn hash_file(file_path: &Path) -> io::Result<String> {
+    let content = fs::read(file_path)?;
+    let hash = format!("{:x}", Sha256::digest(&content));
+    let name = file_path.file_name().unwrap().to_string_lossy();
+
+    let hashed_name = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => format!("{}.{}.{}", stem, &hash[..8], extension),
+        _ => format!("{}.{}", name, &hash[..8]),
+    };
+
+    fs::rename(file_path, file_path.with_file_name(&hashed_name))?;
+    Ok(hashed_name)
+}
+
+/// Replaces every `url(...)` in the css that points to a hashed asset. Relative urls are resolved against /css/.
+fn rewrite_css_urls(css: &str, hashed_filenames: &BTreeMap<String, String>) -> String {
+    let pattern = Regex::new(r#"url\(\s*(['"]?)([^'")?#]+)([^'")]*)(['"]?)\s*\)"#).unwrap();
+
+    pattern.replace_all(css, |captures: &Captures| {
+        let url = &captures[2];
+        let absolute_url = if url.starts_with('/') { url.to_string() } else { resolve_relative_url("/css/", url) };
+
+        match hashed_filenames.get(&absolute_url) {
+            Some(hashed_url) => format!("url({}{}{}{})", &captures[1], hashed_url, &captures[3], &captures[4]),
+            None => captures[0].to_string(),
+        }
+    }).to_string()
+}
+
+fn resolve_relative_url(base: &str, url: &str) -> String {
+    let mut segments: Vec<&str> = base.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in url.split('/') {
+        match segment {
+            "." | "" => {}
+            ".." => { segments.pop(); }
+            segment => segments.push(segment),
+        }
+    }
+    format!("/{}", segments.join("/"))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_hash_filenames() {
+        let dist_path = TempDir::new("hash");
+        fs::create_dir_all(dist_path.join("assets/img")).unwrap();
+        fs::create_dir_all(dist_path.join("css")).unwrap();
+        fs::write(dist_path.join("assets/img/logo.png"), "png").unwrap();
+        fs::write(dist_path.join("css/style.css"), "a{background:url('../img/logo.png')}b{background:url(/img/logo.png?x=1)}").unwrap();
+
+        let hashed_filenames = hash_filenames(&dist_path).unwrap();
+        let hashed_logo = &hashed_filenames["/img/logo.png"];
+        assert!(hashed_logo.starts_with("/img/logo.") && hashed_logo.ends_with(".png"));
+        assert!(dist_path.join("assets").join(&hashed_logo[1..]).exists());
+        assert!(!dist_path.join("assets/img/logo.png").exists());
+
+        let hashed_css = &hashed_filenames["/css/style.css"];
+        let css = fs::read_to_string(dist_path.join(&hashed_css[1..])).unwrap();
+        assert_eq!(css, format!("a{{background:url('{0}')}}b{{background:url({0}?x=1)}}", hashed_logo));
+
+        remove_previous_hashed_files(&dist_path).unwrap();
+        assert!(!dist_path.join(&hashed_css[1..]).exists());
+        assert!(!dist_path.join(HASHED_FILENAMES_FILENAME).exists());
+    }
+}
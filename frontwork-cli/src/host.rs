@@ -0,0 +1,114 @@
+use std::{env, fs, process::Command};
+
+
+/// The target triple of the deno release that runs on this machine
+pub fn deno_target() -> Result<&'static str, String> {
+    deno_target_for(env::consts::OS, env::consts::ARCH, is_musl())
+}
+
+fn deno_target_for(os: &str, arch: &str, musl: bool) -> Result<&'static str, String> {
+    match (os, arch) {
+        ("linux", _) if musl => Err("deno does not publish builds for musl libc (e.g. Alpine Linux). Please use a glibc based distribution or container image.".to_string()),
+        ("linux", "x86_64") => Ok("x86_64-unknown-linux-gnu"),
+        ("linux", "aarch64") => Ok("aarch64-unknown-linux-gnu"),
+        ("macos", "x86_64") => Ok("x86_64-apple-darwin"),
+        ("macos", "aarch64") => Ok("aarch64-apple-darwin"),
+        ("windows", "x86_64") => Ok("x86_64-pc-windows-msvc"),
+        ("windows", "aarch64") => Ok("aarch64-pc-windows-msvc"),
+        _ => Err(format!(
+            "deno does not publish builds for {} on {}. Supported are x86_64 and aarch64 on linux, macos and windows; on a Raspberry Pi this requires a 64-bit OS.",
+            arch, os
+        )),
+    }
+}
+
+/// Whether the C library of this system is musl. Decided at runtime from the dynamic loader of /bin/sh,
+/// because this CLI may be built for musl and run on glibc or the other way around.
+fn is_musl() -> bool {
+    if env::consts::OS != "linux" {
+        return false;
+    }
+
+    // the dynamic loader of musl is /lib/ld-musl-{arch}.so.1, the one of glibc /lib64/ld-linux-{arch}.so.2
+    if let Some(interpreter) = fs::read("/bin/sh").ok().and_then(|elf| elf_interpreter(&elf)) {
+        return interpreter.contains("ld-musl-");
+    }
+
+    // /bin/sh is statically linked or unreadable; musl's ldd prints its version to stderr
+    Command::new("ldd").arg("--version").output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains("musl") || String::from_utf8_lossy(&output.stderr).contains("musl"))
+        .unwrap_or(false)
+}
+
+/// The PT_INTERP program header of an ELF executable
+fn elf_interpreter(elf: &[u8]) -> Option<String> {
+    const PT_INTERP: u32 = 3;
+    if elf.get(..4)? != b"\x7fELF" {
+        return None;
+    }
+    let is_64_bit = *elf.get(4)? == 2;
+    let is_little_endian = *elf.get(5)? == 1;
+    let read = |offset: usize, size: usize| -> Option<usize> {
+        let bytes = elf.get(offset..offset.checked_add(size)?)?;
+        let mut value = [0u8; 8];
+        let value = match is_little_endian {
+            true => { value[..size].copy_from_slice(bytes); u64::from_le_bytes(value) }
+            false => { value[8 - size..].copy_from_slice(bytes); u64::from_be_bytes(value) }
+        };
+        usize::try_from(value).ok()
+    };
+
+    let (phoff, phentsize, phnum) = match is_64_bit {
+        true => (read(0x20, 8)?, read(0x36, 2)?, read(0x38, 2)?),
+        false => (read(0x1c, 4)?, read(0x2a, 2)?, read(0x2c, 2)?),
+    };
+    for index in 0..phnum {
+        let header = phoff.checked_add(index.checked_mul(phentsize)?)?;
+        if read(header, 4)? != PT_INTERP as usize {
+            continue;
+        }
+        let (offset, size) = match is_64_bit {
+            true => (read(header + 0x08, 8)?, read(header + 0x20, 8)?),
+            false => (read(header + 0x04, 4)?, read(header + 0x10, 4)?),
+        };
+        let interpreter = elf.get(offset..offset.checked_add(size)?)?;
+        return Some(String::from_utf8_lossy(interpreter).trim_end_matches('\0').to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deno_target_for() {
+        assert_eq!(deno_target_for("linux", "x86_64", false).unwrap(), "x86_64-unknown-linux-gnu");
+        assert_eq!(deno_target_for("linux", "aarch64", false).unwrap(), "aarch64-unknown-linux-gnu");
+        assert_eq!(deno_target_for("macos", "aarch64", false).unwrap(), "aarch64-apple-darwin");
+        assert_eq!(deno_target_for("windows", "aarch64", false).unwrap(), "aarch64-pc-windows-msvc");
+        assert!(deno_target_for("linux", "x86_64", true).is_err());
+        assert!(deno_target_for("linux", "arm", false).is_err());
+        assert!(deno_target_for("freebsd", "x86_64", false).is_err());
+    }
+
+    #[test]
+    fn test_elf_interpreter() {
+
+        // ELF64 little endian with one PT_INTERP program header right after the file header
+        let mut elf = vec![0u8; 0x40 + 0x38];
+        elf[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        elf[0x20] = 0x40;
+        elf[0x36] = 0x38;
+        elf[0x38] = 1;
+        elf[0x40] = 3;
+        elf[0x48] = 0x78;
+        elf[0x60] = 26;
+        elf.extend_from_slice(b"/lib/ld-musl-x86_64.so.1\0\0");
+        assert_eq!(elf_interpreter(&elf).unwrap(), "/lib/ld-musl-x86_64.so.1");
+
+        elf[0x40] = 1;
+        assert_eq!(elf_interpreter(&elf), None);
+        assert_eq!(elf_interpreter(b"#!/bin/sh"), None);
+    }
+}
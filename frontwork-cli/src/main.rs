@@ -1,19 +1,46 @@
-use convert_case::{Case, Casing};
-use environment_platform::Environment;
+use build_manifest::BuildManifest;
+use build_report::{BuildReport, RunningStep, StepResult};
+use clap::{CommandFactory, Parser};
+use cli::{BuildOptions, Cli, ComponentCommand, GenerateKind, SubCommand};
+use config::FrontworkConfig;
+use environment_overlay::EnvironmentOverlay;
+use environment_platform::Platform;
 use include_dir::{include_dir, Dir};
 use regex::Regex;
-use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::{self, Child};
+use std::process;
 use std::{env, fs};
-use std::{thread, time};
-use utils::{create_dir_all_verbose, read_from_line, run_command, transverse_directory};
-use anyhow::Result;
-
+use std::time;
+use utils::{create_dir_all_verbose, read_from_line, run_command};
+use reload::{ReloadMessage, ReloadServer};
+use supervisor::Supervisor;
+use watcher::{RebuildPlan, SourceWatcher};
+
+mod build_manifest;
+mod build_report;
+mod cli;
+mod component;
+mod config;
+mod deno;
+mod deno_release;
+mod doctor;
+mod desktop;
 mod download;
+mod hash_filenames;
+mod host;
+mod environment_overlay;
 mod environment_platform;
+mod generate;
 mod package_json;
+mod reload;
+mod routes_file;
+mod scaffold;
+mod self_update;
+mod shell;
+mod supervisor;
+mod typescript;
 mod utils;
+mod watcher;
 
 static PROJECT_TEMPLATE_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/template/");
 static BUNDLE_TS_FILE_STR: &str = include_str!("../template/bundle.ts");
@@ -22,153 +49,29 @@ fn print_header_version() {
     println!("Frontwork CLI Tool v{} by LuceusXylian <luceusxylian@gmail.com> and frontwork-org <https://github.com/frontwork-org> Contributors", env!("CARGO_PKG_VERSION"));
 }
 
-fn print_help(no_error: bool, error_message: &str) {
-    print!("\n");
-    if no_error {
-        println!("The usage of arguments has been entered wrong because {}. \nPlease follow the following usage:", error_message);
-    } else {
-        print_header_version();
-        println!("-- The TypeScript Framework using Deno & Webassembly --");
-        println!("\n Usage:");
-    }
-    println!("  -h or --help                    | this help message");
-    println!("  install                         | install required dependencies to develop with Frontwork");
-    println!("  init                            | create a new project in the current directory");
-    println!("  new                             | create a new folder in the current directory and then execute init");
-    println!("  component new                   | create a new component");
-    println!("  component remove                | remove a component");
-    println!("  run                             | run the script of the entered name in package.json");
-    println!("  test                            | run main.testworker.ts");
-    println!("  build                           | build the application to the dist folder. Optional use: --production or --staging");
-    println!("  watch                           | start development server and build the application on changes");
-    println!("  update                          | update Frontwork dependencies to the current version of this CLI tool.");
-    println!("");
-}
-
-#[derive(PartialEq)]
-pub enum SubCommand {
-    Version,
-    Install,
-    Init,
-    New,
-    Component,
-    Run,
-    Test,
-    Build,
-    Watch,
-    Update,
-}
-
-pub enum Flag {
-    Default,
-    New,
-    Remove,
-}
-
-struct Arguments {
-    subcomand: SubCommand,
-    flag: Flag,
-    input: Option<String>,
-}
-
-impl Arguments {
-    fn new(args: &[String]) -> Result<Arguments, &'static str> {
-        if args.len() < 2 {
-            return Err("no arguments have been entered");
-        }
-
-        if args.contains(&"-h".to_string()) || args.contains(&"--help".to_string()) {
-            print_help(true, "");
-            return Err("");
-        } else if args.contains(&"--version".to_string()) {
-            return Ok(Self { subcomand: SubCommand::Version, flag: Flag::Default, input: None });
-        } else {
-            let subcommand: SubCommand = match args[1].as_str() {
-                "install" => SubCommand::Install,
-                "init" => SubCommand::Init,
-                "new" => SubCommand::New,
-                "component" => SubCommand::Component,
-                "run" => SubCommand::Run,
-                "test" => SubCommand::Test,
-                "build" => SubCommand::Build,
-                "watch" => SubCommand::Watch,
-                "update" => SubCommand::Update,
-                _ => return Err("the entered subcommand is not valid"),
-            };
-
-            let flag: Flag = if subcommand == SubCommand::Component {
-                if args.len() < 3 {
-                    return Err("the entered subcommand is not valid");
-                }
-
-                match args[2].as_str() {
-                    "new" => Flag::New,
-                    "add" => Flag::New,
-                    "remove" => Flag::Remove,
-                    _ => return Err("the entered subcommand is not valid"),
-                }
-            } else {
-                Flag::Default
-            };
-
-            let input: Option<String> = if subcommand == SubCommand::New {
-                if args.len() < 3 {
-                    Some(read_from_line("Please enter a name for the new project: "))
-                } else {
-                    Some(args[2].clone())
-                }
-            } else if subcommand == SubCommand::Run {
-                if args.len() < 3 {
-                    Some(read_from_line(
-                        "Please enter the name of the script to run: ",
-                    ))
-                } else {
-                    Some(args[2].clone())
-                }
-            } else if subcommand == SubCommand::Component {
-                if args.len() < 4 {
-                    Some(read_from_line("Please enter the name for the component: "))
-                } else {
-                    Some(args[3].clone())
-                }
-            } else {
-                None
-            };
-
-            return Ok(Arguments {
-                subcomand: subcommand,
-                flag,
-                input,
-            });
-        }
-    }
-}
-
 #[tokio::main]
 async fn main() {
-    let args: Vec<String> = env::args().collect();
-    let arguments = Arguments::new(&args).unwrap_or_else(|err| {
-        if err == "" {
-            process::exit(0);
-        } else {
-            print_help(false, err);
-            process::exit(2);
-        }
-    });
+    let cli = Cli::parse_from(cli::hoist_leading_flags(env::args().collect()));
 
-    match arguments.subcomand {
-        SubCommand::Version => {
-            print_header_version();
-        }
+    if cli.version {
+        print_header_version();
+        return;
+    }
+
+    let Some(command) = cli.command else {
+        Cli::command().print_help().ok();
+        process::exit(2);
+    };
 
-        SubCommand::Install => {
-            command_install().await;
+    match command {
+        SubCommand::Install { deno_version, project, no_modify_path } => {
+            command_install(deno_version, project, no_modify_path).await;
         }
 
-        SubCommand::Init | SubCommand::New => {
-            let project_path = if arguments.subcomand == SubCommand::New {
+        SubCommand::Init | SubCommand::New { .. } => {
+            let project_path = if let SubCommand::New { name } = command {
                 // try to create folder with the name of the project
-                let projectname = arguments.input.unwrap();
+                let projectname = name.unwrap_or_else(|| read_from_line("Please enter a name for the new project: "));
                 let projectpath_local = format!(
                     "{}/{}",
                     env::current_dir().unwrap().to_str().unwrap(),
@@ -186,199 +89,96 @@ async fn main() {
             };
 
             // https://docs.rs/include_dir/latest/include_dir/
-            if let Ok(_) = PROJECT_TEMPLATE_DIR.extract(&project_path) {
+            if PROJECT_TEMPLATE_DIR.extract(&project_path).is_ok() {
                 println!("The project has been initialized successfully.");
             } else {
                 println!("The project initialisation failed.");
             }
         }
 
-        SubCommand::Component => {
+        SubCommand::Component { command } => {
             // Sanity check: Is the project initialized && does ./src/components exist?
-            let project_path = get_project_path();
-            let components_path = format!("{}/src/components", project_path);
-
-            let componentname = arguments.input.unwrap().to_case(Case::Snake);
-            let componentname_uppercamelcase = componentname.to_case(Case::UpperCamel);
-            let componentname_classname = format!("{}Component", componentname_uppercamelcase);
-            let componentpath = format!("{}/{}", components_path, componentname);
-            let global_style_file_path = format!("{}/src/style.scss", project_path);
-            let global_style_content = format!(
-                "\n@import './components/{}/{}.scss';",
-                componentname, componentname
-            );
-            let routes_file_path = format!("{}/src/components/routes.ts", project_path);
-            let component_import_statement = format!(
-                "import {{ {} }} from \"./{}/{}.ts\";",
-                componentname_classname, componentname, componentname
-            );
-
-            match arguments.flag {
-                Flag::New => {
-                    // Create the component
-                    if Path::new(&componentpath).exists() {
-                        println!("The componentname has been used. Please use another name.");
+            let config = get_project_config();
+
+            let input = command.name()
+                .cloned()
+                .unwrap_or_else(|| read_from_line("Please enter the name for the component: "));
+            let name = component::ComponentName::parse(&input).unwrap_or_else(|error| {
+                println!("{}", error);
+                process::exit(2);
+            });
+
+            let result = match command {
+                ComponentCommand::New { path, template, .. } => {
+                    let route_path = path.unwrap_or_else(|| name.default_route_path());
+                    component::create(&config, &name, template.as_deref(), &route_path).map(|_| "The component has been created successfully.")
+                }
+                ComponentCommand::Remove { .. } => {
+                    component::remove(&config, &name).map(|_| "The component has been removed successfully.")
+                }
+                ComponentCommand::Rename { new_name, .. } => {
+                    let input = new_name.unwrap_or_else(|| read_from_line("Please enter the new name for the component: "));
+                    let new_name = component::ComponentName::parse(&input).unwrap_or_else(|error| {
+                        println!("{}", error);
                         process::exit(2);
-                    } else {
-                        fs::create_dir_all(&componentpath).unwrap();
-
-                        let mut ts_file_content = String::new();
-                        ts_file_content.push_str("import { Component, FrontworkContext, DocumentBuilder, FrontworkResponse, FrontworkClient } from \"../../dependencies.ts\";\n\n\n");
-                        ts_file_content.push_str(&format!(
-                            "export class {} implements Component {{\n",
-                            componentname_classname
-                        ));
-                        ts_file_content.push_str(
-                            "    constructor(context: FrontworkContext) {\n        \n    }\n\n",
-                        );
-                        ts_file_content.push_str("    async build(context: FrontworkContext) {\n");
-                        ts_file_content.push_str(
-                            "        const document_builder = new DocumentBuilder(context);\n",
-                        );
-                        ts_file_content.push_str(&format!(
-                            "        const title = '{}';\n",
-                            componentname_uppercamelcase
-                        ));
-                        ts_file_content.push_str(&format!(
-                            "        const description = '{}';\n",
-                            componentname_uppercamelcase
-                        ));
-                        ts_file_content.push_str("        \n");
-                        ts_file_content.push_str("        return await new FrontworkResponse(200, \n");
-                        ts_file_content.push_str("            document_builder\n");
-                        ts_file_content.push_str("                .add_head_meta_data(title, description, \"index,follow\")\n");
-                        ts_file_content.push_str("        );\n");
-                        ts_file_content.push_str("    }\n\n");
-                        ts_file_content.push_str("    async dom_ready(context: FrontworkContext, client: FrontworkClient) {\n        \n    }\n");
-                        ts_file_content.push_str("    async on_destroy(context: FrontworkContext, client: FrontworkClient) {\n        \n    }\n");
-                        ts_file_content.push_str("}\n");
-
-                        fs::write(
-                            Path::new(&format!("{}/{}.ts", componentpath, componentname)),
-                            ts_file_content,
-                        )
-                        .expect("Unable to write file");
-                        fs::write(
-                            Path::new(&format!("{}/{}.scss", componentpath, componentname)),
-                            "",
-                        )
-                        .expect("Unable to write file");
-
-                        // open routes.ts and add the import statement
-                        let mut routes_file_content = String::new();
-                        routes_file_content.push_str(&component_import_statement);
-                        routes_file_content.push_str("\n");
-                        routes_file_content += fs::read_to_string(Path::new(&routes_file_path))
-                            .expect(&format!("Can not open routes.ts  \"{}\"", routes_file_path))
-                            .as_str();
-                        fs::write(Path::new(&routes_file_path), routes_file_content).expect(
-                            &format!("Unable to write routes.ts  \"{}\"", routes_file_path),
-                        );
-
-                        // add the new created component to style.scss
-                        let global_style_file = fs::OpenOptions::new()
-                            .write(true)
-                            .append(true)
-                            .open(&global_style_file_path);
-
-                        match global_style_file {
-                            Ok(mut global_style_file) => {
-                                global_style_file
-                                    .write_all(global_style_content.as_bytes())
-                                    .expect("Unable to write file");
-                                println!("The component has been created successfully.");
-                            }
-                            Err(error) => {
-                                println!("{}", error);
-                                println!(
-                                    "Unable to open '{}'. The project may not be initialized.",
-                                    &global_style_file_path
-                                );
-                                process::exit(2);
-                            }
+                    });
+                    component::rename(&config, &name, &new_name).map(|touched_files| {
+                        for touched_file in touched_files {
+                            println!("{}", touched_file);
                         }
-                    }
+                        "The component has been renamed successfully."
+                    })
                 }
-                Flag::Remove => {
-                    // Delete the component
-                    if Path::new(&componentpath).exists() {
-                        fs::remove_dir_all(&componentpath).unwrap();
-
-                        let mut routes_file_content = String::new();
-                        fs::read_to_string(Path::new(&routes_file_path))
-                            .expect(&format!("Can not open routes.ts  \"{}\"", routes_file_path))
-                            .lines()
-                            .for_each(|line| {
-                                if line != component_import_statement {
-                                    routes_file_content.push_str(line);
-                                    routes_file_content.push_str("\n");
-                                }
-                            });
-                        fs::write(Path::new(&routes_file_path), routes_file_content).expect(
-                            &format!("Unable to write routes.ts  \"{}\"", routes_file_path),
-                        );
-
-                        // remove the component from style.scss
-                        let global_style_file = fs::OpenOptions::new()
-                            .read(true)
-                            .open(&global_style_file_path);
-
-                        match global_style_file {
-                            Ok(mut global_style_file) => {
-                                let mut content = String::new();
-                                global_style_file.read_to_string(&mut content).unwrap();
-                                content = content.replace(global_style_content.as_str(), "");
-                                fs::write(Path::new(&global_style_file_path), content)
-                                    .expect("Unable to write file");
-                            }
-                            Err(error) => {
-                                println!("{}", error);
-                                println!(
-                                    "Unable to open '{}'. The project may not be initialized.",
-                                    &global_style_file_path
-                                );
-                                process::exit(2);
-                            }
-                        }
+            };
+            match result {
+                Ok(message) => println!("{}", message),
+                Err(error) => {
+                    println!("{}", error);
+                    process::exit(2);
+                }
+            }
+        }
 
-                        println!("The component has been removed successfully.");
-                    } else {
-                        println!("The component does not exist.");
+        SubCommand::Generate { kind } => {
+            let config = get_project_config();
+            let result = match kind {
+                GenerateKind::Subservice { name, template } => generate::subservice(&config, &name, template.as_deref()),
+                GenerateKind::Middleware { name, template } => generate::middleware(&config, &name, template.as_deref()),
+                GenerateKind::DocumentBuilder { name, template } => generate::document_builder(&config, &name, template.as_deref()),
+                GenerateKind::Locale { locale } => generate::locale(&config, &locale),
+            };
+            match result {
+                Ok(touched_files) => {
+                    for path in touched_files {
+                        println!("Wrote {}", path.display());
                     }
                 }
-
-                _ => {
-                    println!("The component subcommand is not implemented yet.");
+                Err(error) => {
+                    println!("{}", error);
+                    process::exit(2);
                 }
             }
         }
 
-        SubCommand::Run => {
-            if let Some(input) = arguments.input {
-                let project_path = get_project_path();
-                let package_json = package_json::PackageJson::from_project_path(project_path);
+        SubCommand::Run { script } => {
+            let input = script.unwrap_or_else(|| read_from_line("Please enter the name of the script to run: "));
+            let config = get_project_config();
+            let package_json = package_json::PackageJson::from_project_path(config.project_path);
 
-                if let Some(script) = package_json.scripts.get(&input) {
-                    run_command(script.to_string());
-                } else {
-                    println!("The script '{}' does not exist.", input);
-                }
+            if let Some(script) = package_json.scripts.get(&input) {
+                let status = run_command(script.to_string()).wait().expect("failed to wait for script");
+                process::exit(status.code().unwrap_or(1));
             } else {
-                print_help(false, "missing input");
-                process::exit(2);
+                println!("The script '{}' does not exist.", input);
             }
         }
 
         SubCommand::Test => {
-            let project_path = get_project_path();
-            let main_testworker_file_path = format!("{}/src/main.testworker.ts", project_path);
-
-            // deno run src/testworker.service.ts
-            let process = process::Command::new("deno")
-                .arg("run")
-                .arg("--allow-read")
-                .arg("--allow-net")
-                .arg("--allow-env")
+            let config = get_project_config();
+            let main_testworker_file_path = format!("{}/main.testworker.ts", config.src_path());
+
+            // deno run src/main.testworker.ts
+            let process = deno::command("run", &config, &config.deno.permissions.test)
                 .arg(main_testworker_file_path)
                 .spawn()
                 .expect("failed to execute process")
@@ -388,25 +188,17 @@ async fn main() {
             process::exit(if process.success() { 0 } else { 1 });
         }
 
-        SubCommand::Build => {
-            let environment = if args.contains(&"--staging".to_string()) {
-                Environment::Staging
-            } else if args.contains(&"--development".to_string()) {
-                Environment::Development
-            } else {
-                Environment::Production
-            };
-
-            let target = args.iter().position(|arg| arg == "--target")
-                .and_then(|i| args.get(i + 1))
-                .map(|s| s.to_string())
-                .unwrap_or("x86_64-unknown-linux-gnu".to_string());
-
-            command_build(environment, target);
+        SubCommand::Build(options) => {
+            if !command_build(&options, &get_project_config()) {
+                process::exit(1);
+            }
         }
 
         SubCommand::Watch => {
-            command_watch();
+            if let Err(error) = command_watch(get_project_config()) {
+                eprintln!("ERROR {}", error);
+                process::exit(1);
+            }
         }
 
         SubCommand::Update => {
@@ -414,326 +206,498 @@ async fn main() {
                 println!("Error while running Subcommand \"update\": \n{:#?}", e);
             }
         }
+
+        SubCommand::SelfUpdate => {
+            println!("Current version: {}", env!("CARGO_PKG_VERSION"));
+            match self_update::self_update().await {
+                Ok(Some(version)) => println!("Frontwork CLI Tool was updated to {}", version),
+                Ok(None) => println!("Frontwork CLI Tool is up to date"),
+                Err(error) => {
+                    println!("Self-update failed: {:#}", error);
+                    process::exit(1);
+                }
+            }
+        }
+
+        SubCommand::Uninstall { project } => {
+            command_uninstall(project);
+        }
+
+        SubCommand::Doctor { json } => {
+            let checks = doctor::run_checks(env::current_dir().unwrap().to_str().unwrap());
+            if json {
+                println!("{}", serde_json::to_string_pretty(&checks).unwrap());
+            } else {
+                doctor::print_checks(&checks);
+            }
+
+            if checks.iter().any(|check| check.status == doctor::CheckStatus::Error) {
+                process::exit(1);
+            }
+        }
     }
 }
 
-fn get_project_path() -> String {
+/// Reads the frontwork.toml of the project in the current directory. Exits if the current directory is not a frontwork project.
+fn get_project_config() -> FrontworkConfig {
     let project_path = env::current_dir().unwrap().to_str().unwrap().to_string();
-    let package_json_path = format!("{}/package.json", project_path);
-    let components_path = format!("{}/src/components", project_path);
-    if !Path::new(&package_json_path).exists() || !Path::new(&components_path).exists() {
+    FrontworkConfig::from_project_directory(&project_path).unwrap_or_else(|error| {
+        eprintln!("ERROR {}", error);
         println!("The current directory is not a frontwork project directory. Please change directory or run 'frontwork init' to initialize the project first.");
         process::exit(1);
-    }
-
-    project_path
+    })
 }
 
-async fn command_install() {
-    println!("GET & INSTALL: Deno");
+async fn command_install(deno_version: Option<String>, project: bool, no_modify_path: bool) {
+    // --deno-version, otherwise engines.deno of the project in the current directory
+    let requested_version = deno_version
+        .or_else(|| package_json::PackageJson::read(env::current_dir().unwrap().to_str().unwrap()).ok()?.engines.get("deno").cloned())
+        .unwrap_or("latest".to_string());
+    println!("GET & INSTALL: Deno {}", requested_version);
 
-    // Check if deno is already installed
-    // let deno_is_installed = std::process::Command::new("deno")
-    //     .arg("help")
-    //     .output()
-    //     .is_ok();
+    let target = host::deno_target().unwrap_or_else(|error| {
+        println!("{}", error);
+        process::exit(1);
+    });
 
-    if false {
-        println!("Deno is already installed");
+    // ~/.deno, or the toolchain directory of the project for CI containers without a writable home
+    let deno_install = if project {
+        format!("{}/{}", get_project_config().project_path, deno::PROJECT_TOOLCHAIN_DIR)
     } else {
-        let target = if cfg!(target_os = "windows") {
-            "x86_64-pc-windows-msvc"
-        } else if cfg!(target_os = "macos") {
-            if cfg!(target_arch = "aarch64") {
-                "aarch64-apple-darwin"
-            } else {
-                "x86_64-apple-darwin"
-            }
-        } else {
-            "x86_64-unknown-linux-gnu"
-        };
+        env::var("HOME").unwrap() + "/.deno"
+    };
+    let bin_dir = deno_install.clone() + "/bin";
+    let bin_file = deno_install.clone() + "/bin/deno";
+
+    // Check if the installed deno is already the requested version
+    let installed_version = deno::binary_version(&bin_file).and_then(|version| semver::Version::parse(&version).ok());
+    if let (Some(installed_version), Ok(range)) = (&installed_version, deno::VersionRange::parse(requested_version.trim_start_matches('v'))) {
+        if range.matches(installed_version) {
+            println!("Deno {} is already installed", installed_version);
+            return;
+        }
+    }
 
-        let homedir = env::var("HOME").unwrap();
-        let deno_uri = format!(
-            "https://github.com/denoland/deno/releases/latest/download/deno-{}.zip",
-            target
-        );
-        let deno_install = homedir.clone() + "/.deno";
-        let bin_dir = deno_install.clone() + "/bin";
-        let bin_file = deno_install.clone() + "/bin/deno";
+    let mut download_options = download::DownloadOptions::default();
+    let version = deno_release::resolve_version(&requested_version, &download_options).await.unwrap_or_else(|error| {
+        println!("Unable to find deno {}: {:#}", requested_version, error);
+        process::exit(1);
+    });
+    if installed_version.as_ref() == Some(&version) {
+        println!("Deno {} is already installed", version);
+        return;
+    }
 
-        // Create Deno installation directory if not exists
-        create_dir_all_verbose(&bin_dir);
+    let deno_uri = deno_release::archive_url(&version, target);
+    let expected_sha256 = deno_release::fetch_sha256(&deno_uri, &download_options).await.unwrap_or_else(|error| {
+        println!("Unable to verify the download of deno {}: {:#}", version, error);
+        process::exit(1);
+    });
+    download_options.expected_sha256 = Some(expected_sha256);
 
-        match download::download_large_file(&deno_uri).await {
-            Err(error) => {
-                println!("Download of {} failed", deno_uri);
-                println!("{:?}", error);
-            }
+    // Create Deno installation directory if not exists
+    create_dir_all_verbose(&bin_dir);
 
-            Ok(archive_file) => {
-                println!("Saved archive to: {:#?}", archive_file);
-                // Delete old binary file
-                let _ = std::fs::remove_file(&bin_file);
+    match download::download_large_file(&deno_uri, &download_options).await {
+        Err(error) => {
+            println!("Download of {} failed", deno_uri);
+            println!("{:?}", error);
+        }
 
-                // Download successful, now unzip it
-                let archive_file: PathBuf = PathBuf::from(archive_file);
-                let target_dir: PathBuf = PathBuf::from(&bin_dir);
-                if let Err(err) = crate::utils::zip_extract(&archive_file, &target_dir) {
-                    println!("Extration of archive failed.\n\n{:#?}", err);
+        Ok(download) => {
+            println!("Saved archive to: {:#?}", download.path);
+            println!("Verified SHA-256 sum {}", download.sha256);
+
+            // Delete old binary file
+            let _ = std::fs::remove_file(&bin_file);
+
+            // Download successful, now unzip it
+            let archive_file: PathBuf = download.path;
+            let target_dir: PathBuf = PathBuf::from(&bin_dir);
+            if let Err(err) = crate::utils::zip_extract(&archive_file, &target_dir) {
+                println!("Extration of archive failed.\n\n{:#?}", err);
+            } else {
+                if let Err(err) = utils::make_file_executable(&bin_file) {
+                    println!("Unable to make file executable.\n\n{:#?}", err);
                 } else {
-                    if let Err(err) = utils::make_file_executable(&bin_file) {
-                        println!("Unable to make file executable.\n\n{:#?}", err);
+                    println!("Saved deno executable to: {:#?}", bin_file);
+
+                    // Add path env of the executable
+                    let shell = shell::Shell::from_env();
+                    if project {
+                        println!("The frontwork commands of this project use this deno instead of the one on PATH.");
+                    } else if no_modify_path {
+                        println!("Add these lines to your shell profile to put deno on PATH:\n{}", shell.export_lines(&deno_install));
                     } else {
-                        println!("Saved deno executable to: {:#?}", bin_file);
-
-                        // Add path env of the executable
-                        let bashrc_path = homedir + "/.bashrc";
-                        let bashrc_content = if Path::new(&bashrc_path).exists() {
-                            fs::read_to_string(&bashrc_path)
-                                .expect(".bashrc should be readable")
-                        } else {
-                            String::new() // Create empty string for new .bashrc
-                        };
-
-                        if !bashrc_content.contains("DENO_INSTALL") {
-                            let mut new_bashrc = bashrc_content;
-                            new_bashrc += "\n\n";
-                            new_bashrc += &format!("export DENO_INSTALL=\"{}\"\n", deno_install);
-                            new_bashrc += &"export PATH=\"$DENO_INSTALL/bin:$PATH\"\n".to_string();
-
-                            fs::write(&bashrc_path, new_bashrc)
-                                .expect(".bashrc should be writeable");
-                            println!("Deno was installed successfully to {}", bin_file);
-                            println!("Please restart shell to start using it.");
+                        match shell.add_to_path(Path::new(&env::var("HOME").unwrap()), &deno_install) {
+                            Ok(modified_profiles) => {
+                                for profile_path in &modified_profiles {
+                                    println!("Added deno to PATH in {}", profile_path.display());
+                                }
+                                if !modified_profiles.is_empty() {
+                                    println!("Please restart shell to start using it, or run:\n{}", shell.export_lines(&deno_install));
+                                }
+                            }
+                            Err(error) => {
+                                println!("Unable to add deno to PATH: {}", error);
+                                println!("Add these lines to your shell profile:\n{}", shell.export_lines(&deno_install));
+                            }
                         }
                     }
+                    println!("Deno {} was installed successfully to {}", version, bin_file);
                 }
             }
         }
     }
 }
 
-fn command_build(environment: Environment, target: String) {
-    println!("Building Frontwork-Project for {}", environment.to_str());
-
-    // TODO: category build; dist/web, dist/electron, dist/android, dist/ios
-    // new build path: /dist/{environment}-{platform}/
-    let project_path = get_project_path();
-    let platform = "web";
-    let dist_web_path = format!(
-        "{}/dist/{}-{}",
-        project_path,
-        environment.to_str_lcase(),
-        platform
-    );
-
-    // environment: move respective files
-    // File pattern: environment.{environment}.{platform}.ts
-    let envfile_dev_path = &format!("{}/src/environments/environment.ts", project_path);
-    let envfile_selected_path = &format!(
-        "{}/src/environments/environment.{}.{}.ts",
-        project_path,
-        environment.to_str_lcase(),
-        platform
-    );
-    let envfile_tempdev_path = &format!(
-        "{}/src/environments/environment.development.web.ts",
-        project_path
-    );
-    if environment != Environment::Development {
-        if !Path::new(envfile_selected_path).exists() {
-            eprintln!(
-                "ERROR environment file ({}) does not exists",
-                envfile_selected_path
-            );
-            return;
-        } else {
-            fs::rename(envfile_dev_path, envfile_tempdev_path)
-                .expect("expected to be able rename file");
-            fs::rename(envfile_selected_path, envfile_dev_path)
-                .expect("expected to be able rename file");
+fn command_uninstall(project: bool) {
+    if project {
+        // the toolchain directory belongs to frontwork entirely
+        let config = get_project_config();
+        let toolchain_path = config.path(deno::PROJECT_TOOLCHAIN_DIR);
+        match fs::remove_dir_all(&toolchain_path) {
+            Ok(()) => println!("Removed {}", toolchain_path),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => println!("Deno is not installed in {}", toolchain_path),
+            Err(error) => {
+                println!("Unable to remove {}: {}", toolchain_path, error);
+                process::exit(1);
+            }
+        }
+        fs::remove_dir(config.path(".frontwork")).ok();
+        return;
+    }
+
+    let home_path = env::var("HOME").unwrap();
+    let deno_install = home_path.clone() + "/.deno";
+    let bin_file = deno_install.clone() + "/bin/deno";
+    match fs::remove_file(&bin_file) {
+        Ok(()) => println!("Removed {}", bin_file),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => println!("Deno is not installed in {}", deno_install),
+        Err(error) => {
+            println!("Unable to remove {}: {}", bin_file, error);
+            process::exit(1);
         }
     }
+    // ~/.deno/bin may also contain scripts installed by 'deno install'; only remove the directories if they are empty
+    if fs::remove_dir(deno_install.clone() + "/bin").is_ok() && fs::remove_dir(&deno_install).is_ok() {
+        println!("Removed {}", deno_install);
+    }
+
+    match shell::remove_from_profiles(Path::new(&home_path), &deno_install) {
+        Ok(modified_profiles) => {
+            for profile_path in &modified_profiles {
+                println!("Removed deno from PATH in {}", profile_path.display());
+            }
+        }
+        Err(error) => {
+            println!("Unable to remove deno from the shell profiles: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+/// Returns false if any build step failed
+fn command_build(options: &BuildOptions, config: &FrontworkConfig) -> bool {
+    let environment = options.environment();
+    let targets = options.targets(&config.build.default_target);
+    let platform = &options.platform;
+    println!("Building Frontwork-Project for {} {}", environment.to_str(), platform.to_str());
+    let build_started = time::Instant::now();
+
+    // build path: /dist/{environment}-{platform}/
+    let dist_web_path = config.dist_path(environment.to_str_lcase(), platform.to_str_lcase());
+    let environments_path = config.path(&config.build.environments_dir);
+
+    // environment: redirect environment.ts to the selected file through an import map; the source files stay untouched
+    // File pattern: environment.{environment}.{platform}.ts
+    let environment_overlay = match EnvironmentOverlay::new(Path::new(&environments_path), Path::new(&config.deno_config_path()), &environment, platform) {
+        Ok(environment_overlay) => environment_overlay,
+        Err(error) => {
+            eprintln!("ERROR {:#}", error);
+            return false;
+        }
+    };
 
     // mkdir dist
     create_dir_all_verbose(&dist_web_path);
+    let dist_path = Path::new(&dist_web_path);
+    // a single target keeps the name main.service
+    let service_binary_names: Vec<String> = targets.iter()
+        .map(|target| if targets.len() == 1 { "main.service".to_string() } else { format!("main.service-{}", target) })
+        .collect();
+
+    let mut build_report = BuildReport::default();
+
+    // remove what the previous build left behind
+    build_report.add(StepResult::measure("clean", || {
+        hash_filenames::remove_previous_hashed_files(dist_path)
+            .map_err(|error| format!("Unable to remove the hashed files of the previous build: {}", error))?;
+        fs::remove_file(dist_path.join(build_manifest::BUILD_MANIFEST_FILENAME)).ok();
+        // also the binaries of other targets, so that switching between single and multiple targets leaves none behind
+        let entries = fs::read_dir(dist_path).map_err(|error| format!("Unable to read {}: {}", dist_path.display(), error))?;
+        for entry in entries.flatten() {
+            let is_service_binary = entry.file_name().to_string_lossy().starts_with("main.service");
+            if is_service_binary && entry.path().is_file() {
+                fs::remove_file(entry.path())
+                    .map_err(|error| format!("Unable to remove {}: {}", entry.path().display(), error))?;
+            }
+        }
+        Ok(())
+    }));
+    if !build_report.is_success() {
+        build_report.print_summary();
+        eprintln!("\nBuild failed.");
+        return false;
+    }
 
-    // build service and client
-    let mut build_service_command = build_service(target, &project_path, &dist_web_path);
-    let mut build_client_command = build_client(&project_path, &dist_web_path);
+    // build service for every target and the client once
+    let build_service_steps: Vec<RunningStep> = targets.iter().zip(&service_binary_names).map(|(target, service_binary_name)| {
+        RunningStep::spawn(
+            format!("service {}", target),
+            &mut build_service_command(target, service_binary_name, config, &dist_web_path, environment_overlay.as_ref()),
+        )
+    }).collect();
+    let build_client_step = RunningStep::spawn("client", &mut build_client_command(config, &dist_web_path, environment_overlay.as_ref()));
 
     // rsync assets
-    build_assets(&project_path, &dist_web_path);
+    build_report.add(StepResult::measure("assets", || build_assets(config, &dist_web_path)));
 
     // build css
-    build_css(&project_path, &dist_web_path);
+    build_report.add(StepResult::measure("css", || build_css(config, &dist_web_path)));
 
     // wait for processes
-    build_service_command.wait().ok();
-    build_client_command.wait().ok();
-
-    // rename files back their original names
-    if environment != Environment::Development {
-        fs::rename(envfile_dev_path, envfile_selected_path)
-            .expect("expected to be able rename file");
-        fs::rename(envfile_tempdev_path, envfile_dev_path)
-            .expect("expected to be able rename file");
+    for build_service_step in build_service_steps {
+        build_report.add(build_service_step.wait());
+    }
+    let build_client_result = build_client_step.wait();
+    let build_client_success = build_client_result.success;
+    build_report.add(build_client_result);
+
+    // bundlers of frontwork 0.4.4 and older ignore FRONTWORK_IMPORT_MAP and would silently bundle the development environment
+    if let (true, Some(environment_overlay)) = (build_client_success, &environment_overlay) {
+        build_report.add(StepResult::measure("client environment", || {
+            if environment_overlay.is_applied_by_bundler() {
+                Ok(())
+            } else {
+                Err(format!("The bundler imported by {} does not support environment overlays, so the client would use environment.ts of development. Run 'frontwork update' to import frontwork-bundler.ts of frontwork {}.", config.build.client_bundle, env!("CARGO_PKG_VERSION")))
+            }
+        }));
+    }
+
+    // fingerprint filenames for far-future caching
+    if options.hash_filenames && build_report.is_success() {
+        build_report.add(StepResult::measure("hash filenames", || {
+            let hashed_filenames = hash_filenames::hash_filenames(dist_path).map_err(|error| error.to_string())?;
+            println!("Added content hashes to {} filenames", hashed_filenames.len());
+            Ok(())
+        }));
+    }
+
+    // bundle main.service with a launcher that opens it in a webview window
+    if *platform == Platform::Desktop && build_report.is_success() {
+        build_report.add(StepResult::measure("package desktop", || {
+            let environment_file_path = environment_overlay::environment_file_path(Path::new(&environments_path), &environment, platform);
+            let app_name = package_json::PackageJson::from_project_path(config.project_path.clone()).name.unwrap_or("frontwork-app".to_string());
+            let archive_path = desktop::package_desktop(dist_path, &environment_file_path, &app_name)?;
+            println!("Saved desktop package to {}", archive_path.display());
+            Ok(())
+        }));
+    }
+
+    // record what has been built
+    if build_report.is_success() {
+        build_report.add(StepResult::measure("build manifest", || {
+            let build_manifest = BuildManifest::from_dist_path(dist_path, environment.to_str_lcase(), platform.to_str_lcase(), &targets, build_started.elapsed())
+                .map_err(|error| format!("Unable to create {}: {}", build_manifest::BUILD_MANIFEST_FILENAME, error))?;
+            build_manifest.write(dist_path)
+                .map_err(|error| format!("Unable to write {}: {}", build_manifest::BUILD_MANIFEST_FILENAME, error))?;
+            println!("Saved {} with {} files", build_manifest::BUILD_MANIFEST_FILENAME, build_manifest.files.len());
+            Ok(())
+        }));
     }
+
+    build_report.print_summary();
+    if !build_report.is_success() {
+        eprintln!("\nBuild failed.");
+        return false;
+    }
+    true
 }
 
-fn command_watch() {
-    let project_path = get_project_path();
-    let src_path_string = format!("{}/src", project_path);
-    let src_path = Path::new(&src_path_string);
-    let dist_web_path = format!("{}/dist/development-web", project_path);
+/// Rebuilds on changes until Ctrl-C; returns only if watching can not be started
+fn command_watch(config: FrontworkConfig) -> Result<(), String> {
+    let dist_web_path = config.dist_path("development", "web");
 
     // mkdir dist
     create_dir_all_verbose(&dist_web_path);
 
-    // initate watch worker; check src directory if any file changed. If a file changed then build css, client, restart dev server and reload browser page
-    let watch_interval_sleep_duration = time::Duration::from_secs(4);
-    let mut prev_files = transverse_directory(Path::new(&src_path));
-    let mut run_service_process: Option<Child> = None;
+    // initate watch worker; get notified if any file in the src directory changed. Then only rerun the build steps affected by the changed files
+    // paths are canonicalized because the watcher reports them that way
+    let src_path = Path::new(&config.src_path()).canonicalize()
+        .map_err(|error| format!("Unable to watch \"{}\": {}", config.src_path(), error))?;
+    let assets_path = Path::new(&config.path(&config.build.assets_dir)).canonicalize()
+        .unwrap_or_else(|_| PathBuf::from(config.path(&config.build.assets_dir)));
+    let mut watched_paths = vec![src_path.as_path()];
+    if !assets_path.starts_with(&src_path) && assets_path.exists() {
+        watched_paths.push(assets_path.as_path());
+    }
+    let watcher = SourceWatcher::new(&watched_paths, time::Duration::from_millis(config.watch.debounce_ms))
+        .map_err(|error| format!("Unable to watch \"{}\": {}", src_path.display(), error))?;
+    let mut service_supervisor: Option<Supervisor> = None;
+    let mut plan = RebuildPlan::all();
+
+    // kill the service and client processes including their children on Ctrl-C
+    supervisor::install_ctrlc_handler();
+
+    // notifies the browser about rebuilds that do not restart the service
+    let reload_server = ReloadServer::start(&config.watch.reload_address, config.watch.reload_port)
+        .map_err(|error| format!(
+            "Unable to start the reload server on {}:{}: {}. Set another reload_port in the [watch] section of {}.",
+            config.watch.reload_address, config.watch.reload_port, error, config::CONFIG_FILENAME
+        ))?;
 
     loop {
         // build client
-        let mut build_client_command = build_client(&project_path, &dist_web_path);
+        let build_client_process = if plan.client {
+            Some(supervisor::spawn_prefixed(&mut build_client_command(&config, &dist_web_path, None), "client")
+                .expect("Failed to execute deno. Make sure deno is installed on this machine."))
+        } else {
+            None
+        };
 
         // build css
-        build_css(&project_path, &dist_web_path);
+        if plan.css {
+            if let Err(error) = build_css(&config, &dist_web_path) {
+                eprintln!("{}", error);
+            }
+        }
+
+        // rsync assets
+        if plan.assets {
+            if let Err(error) = build_assets(&config, &dist_web_path) {
+                eprintln!("{}", error);
+            }
+        }
 
         // wait for processes
-        build_client_command.wait().ok();
+        if let Some(mut build_client_process) = build_client_process {
+            supervisor::wait(&mut build_client_process).ok();
+        }
 
-        // start/restart service
-        if let Some(mut process) = run_service_process {
-            process.kill().ok();
+        // the client reloads by itself after the service has been restarted
+        if !plan.service {
+            if plan.client {
+                reload_server.broadcast(ReloadMessage::Reload);
+            } else if plan.css {
+                reload_server.broadcast(ReloadMessage::CssChanged);
+            }
         }
-        run_service_process = Some(run_service(&project_path));
-
-        loop {
-            let files = transverse_directory(src_path);
-            let mut had_changes = prev_files.len() != files.len();
-
-            if !had_changes {
-                for file in &files {
-                    let mut found_new_file = true;
-                    for prev_file in &prev_files {
-                        if file.path == prev_file.path {
-                            if file.modified != prev_file.modified {
-                                had_changes = true;
-                                break;
-                            }
-                            found_new_file = false;
-                            break;
-                        }
-                    }
 
-                    if had_changes {
-                        break;
-                    }
-                    if found_new_file {
-                        had_changes = false;
-                        break;
-                    }
+        // start/restart service; the supervisor restarts it by itself if it crashes
+        if plan.service {
+            match &service_supervisor {
+                Some(service_supervisor) => service_supervisor.restart(),
+                None => {
+                    let config = config.clone();
+                    let dist_web_path = dist_web_path.clone();
+                    service_supervisor = Some(Supervisor::start("service", move || run_service_command(&config, &dist_web_path)));
                 }
             }
+        }
 
-            if had_changes {
-                println!("Files changed reload..");
-                prev_files = files;
-                break;
-            } else {
-                thread::sleep(watch_interval_sleep_duration);
-            }
+        let changes = watcher.next_changes();
+        println!("Files changed reload..");
+        for change in &changes {
+            println!("  {}", change);
         }
+        plan = RebuildPlan::from_changes(&assets_path, &changes);
     }
 }
 
-fn build_assets(project_path: &String, dist_web_path: &String) {
+fn build_assets(config: &FrontworkConfig, dist_web_path: &String) -> Result<(), String> {
     utils::rsync(
-        format!("{}/src/assets/", project_path),
+        format!("{}/", config.path(&config.build.assets_dir)),
         format!("{}/assets/", dist_web_path),
-    );
+    )
 }
 
-fn build_css(project_path: &String, dist_web_path: &String) {
+fn build_css(config: &FrontworkConfig, dist_web_path: &String) -> Result<(), String> {
     let dist_css_dir = format!("{}/css", dist_web_path);
     create_dir_all_verbose(&dist_css_dir);
 
     utils::sass(
-        format!("{}/src/style.scss", project_path),
+        config.path(&config.build.style_entry),
         format!("{}/style.css", dist_css_dir),
-    );
+    )?;
+
+    // every extra entry gets its own stylesheet named after the file
+    for style_entry in &config.build.extra_style_entries {
+        let file_stem = Path::new(style_entry).file_stem().and_then(|stem| stem.to_str()).unwrap_or("style");
+        utils::sass(
+            config.path(style_entry),
+            format!("{}/{}.css", dist_css_dir, file_stem),
+        )?;
+    }
+
+    Ok(())
 }
 
-fn build_service(target: String, project_path: &String, dist_web_path: &String) -> process::Child {
-    let service_binary_path = format!("{}/main.service", dist_web_path);
-    if Path::new(&service_binary_path).exists() {
-        fs::remove_file(&service_binary_path).expect("Failed to remove existing binary file");
-    }
+fn build_service_command(target: &str, service_binary_name: &str, config: &FrontworkConfig, dist_web_path: &String, environment_overlay: Option<&EnvironmentOverlay>) -> process::Command {
+    let service_binary_path = format!("{}/{}", dist_web_path, service_binary_name);
 
-    let mut binding = std::process::Command::new("deno");
-    let command = binding
-        .arg("compile")
-        .arg("-c")
-        .arg(format!("{}/deno.jsonc", project_path))
+    let mut command = deno::command("compile", config, &config.deno.permissions.service);
+    command
         .arg("-o")
         .arg(service_binary_path)
         .arg("--target")
-        .arg(target)
-        .arg("--allow-read")
-        .arg("--allow-net")
-        .arg("--allow-env")
-        .arg(format!("{}/src/main.service.ts", project_path));
+        .arg(target);
+    if let Some(environment_overlay) = environment_overlay {
+        command.arg("--import-map").arg(environment_overlay.import_map_path());
+    }
+    command.arg(config.path(&config.build.service_entry));
 
     println!("Program: {}", &command.get_program().to_string_lossy());
     println!("Args: {:?}", &command.get_args().collect::<Vec<_>>());
-
     command
-        .spawn()
-        .expect("Failed to execute deno. Make sure deno is installed on this machine.")
 }
 
-fn build_client(project_path: &String, dist_web_path: &String) -> process::Child {
-    let bundle_ts_path = format!("{}/bundle.ts", project_path);
+fn build_client_command(config: &FrontworkConfig, dist_web_path: &String, environment_overlay: Option<&EnvironmentOverlay>) -> process::Command {
+    let bundle_ts_path = config.path(&config.build.client_bundle);
 
     if !Path::new(&bundle_ts_path).exists() {
         fs::write(&bundle_ts_path, BUNDLE_TS_FILE_STR).expect("Unable to write bundle.ts file");
     }
 
-    std::process::Command::new("deno")
-        .arg("run")
-        .arg("--allow-read")
-        .arg("--allow-write")
-        .arg("--allow-net")
-        .arg("--allow-env")
-        .arg("--allow-run")
+    let mut command = deno::command("run", config, &config.deno.permissions.client);
+    if let Some(environment_overlay) = environment_overlay {
+        // bundle.ts itself imports environment.ts; esbuild reads the import map through FRONTWORK_IMPORT_MAP
+        command
+            .arg("--import-map")
+            .arg(environment_overlay.import_map_path())
+            .env("FRONTWORK_IMPORT_MAP", environment_overlay.import_map_url())
+            .env("FRONTWORK_IMPORT_MAP_RECEIPT", environment_overlay.receipt_path());
+    }
+    command
         .arg(bundle_ts_path)
-        .arg(dist_web_path)
-        .arg("-c")
-        .arg(format!("{}/deno.jsonc", project_path))
-        .spawn()
-        .expect("Failed to execute deno. Make sure deno is installed on this machine.")
+        .arg(dist_web_path);
+    command
 }
 
-fn run_service(project_path: &String) -> process::Child {
-    std::process::Command::new("deno")
-        .arg("run")
-        .arg("--allow-read")
-        .arg("--allow-net")
-        .arg("--allow-env")
-        .arg("-c")
-        .arg(format!("{}/deno.jsonc", project_path))
-        .arg(format!("{}/src/main.service.ts", project_path))
-        .spawn()
-        .expect("Failed to execute deno. Make sure deno is installed on this machine.")
+/// main.service.ts of the template reads the dist folder from FRONTWORK_DIST_DIR in development,
+/// FrontworkWebservice the port of the reload channel from FRONTWORK_RELOAD_PORT
+fn run_service_command(config: &FrontworkConfig, dist_web_path: &String) -> process::Command {
+    let mut command = deno::command("run", config, &config.deno.permissions.service);
+    command
+        .arg(config.path(&config.build.service_entry))
+        .env("FRONTWORK_DIST_DIR", dist_web_path)
+        .env("FRONTWORK_RELOAD_PORT", config.watch.reload_port.to_string());
+    command
 }
 
 
 fn update_frontwork_deps() -> std::io::Result<()> {
-    let project_path = get_project_path();
+    let config = get_project_config();
     let cargo_pkg_version = env!("CARGO_PKG_VERSION");
 
     let pattern = Regex::new(r"https://deno\.land/x/frontwork@[0-9]+\.[0-9]+\.[0-9]+/").unwrap();
@@ -741,10 +705,10 @@ fn update_frontwork_deps() -> std::io::Result<()> {
 
     // You can specify the files you want to search through
     let files = vec![
-        format!("{project_path}/src/dependencies.ts"),
-        format!("{project_path}/src/main.service.ts"),
-        format!("{project_path}/src/main.testworker.ts"),
-        format!("{project_path}/bundle.ts"),
+        format!("{}/dependencies.ts", config.src_path()),
+        config.path(&config.build.service_entry),
+        format!("{}/main.testworker.ts", config.src_path()),
+        config.path(&config.build.client_bundle),
     ];
 
     for file_path in files {
@@ -4,15 +4,21 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
 pub struct PackageJson {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub engines: HashMap<String, String>,
     pub scripts: HashMap<String, String>,
 }
 
 impl PackageJson {
     pub fn from_project_path(project_path: String) -> PackageJson {
+        Self::read(&project_path).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn read(project_path: &str) -> Result<PackageJson, String> {
         let package_json_path_string = &format!("{}/package.json", project_path);
         let package_json_path = Path::new(&package_json_path_string);
-        let package_json_content = fs::read_to_string(package_json_path).expect(&format!("Can not open package.json  \"{}\"", package_json_path.display()));
-        let package_json: PackageJson = serde_json::from_str(&package_json_content).expect(&format!("Unable to parse package.json  \"{}\"", package_json_path.display()));
-        package_json
+        let package_json_content = fs::read_to_string(package_json_path).map_err(|_| format!("Can not open package.json  \"{}\"", package_json_path.display()))?;
+        serde_json::from_str(&package_json_content).map_err(|_| format!("Unable to parse package.json  \"{}\"", package_json_path.display()))
     }
 }
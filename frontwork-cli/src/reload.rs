@@ -0,0 +1,81 @@
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tungstenite::{Message, WebSocket};
+
+
+/// Default of watch.reload_port. The service passes the actual port to the client, `FW.reload_port` of frontwork-std is the fallback.
+pub const RELOAD_PORT: u16 = 35729;
+
+pub enum ReloadMessage {
+    /// Only the stylesheet changed; the client swaps it in place without reloading the page
+    CssChanged,
+    /// The client bundle changed; the client reloads the page
+    Reload,
+}
+
+impl ReloadMessage {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ReloadMessage::CssChanged => "css-changed",
+            ReloadMessage::Reload => "reload",
+        }
+    }
+}
+
+/// WebSocket server of the CLI that notifies the browser about rebuilds in watch mode.
+/// It runs independently from the service process, so it keeps working while the service is not restarted.
+pub struct ReloadServer {
+    clients: Arc<Mutex<Vec<WebSocket<TcpStream>>>>,
+}
+
+impl ReloadServer {
+    pub fn start(address: &str, port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind((address, port))?;
+        let clients: Arc<Mutex<Vec<WebSocket<TcpStream>>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let accepted_clients = clients.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().map_while(Result::ok) {
+                match tungstenite::accept(stream) {
+                    Ok(websocket) => accepted_clients.lock().unwrap().push(websocket),
+                    Err(error) => eprintln!("[reload] handshake failed: {}", error),
+                }
+            }
+        });
+
+        Ok(Self { clients })
+    }
+
+    pub fn broadcast(&self, message: ReloadMessage) {
+        let mut clients = self.clients.lock().unwrap();
+        // drop every client that has disconnected
+        clients.retain_mut(|client| client.send(Message::text(message.as_str())).is_ok());
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_broadcast() {
+        let port = 35730;
+        let server = ReloadServer::start("127.0.0.1", port).unwrap();
+        let (mut client, _) = tungstenite::connect(format!("ws://127.0.0.1:{}", port)).unwrap();
+
+        // wait for the accept thread to register the client
+        for _ in 0..50 {
+            if !server.clients.lock().unwrap().is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        server.broadcast(ReloadMessage::CssChanged);
+        assert_eq!(client.read().unwrap(), Message::text("css-changed"));
+    }
+}
@@ -0,0 +1,137 @@
+//! Edits src/components/routes.ts for `frontwork component new` and `frontwork component remove`.
+//! Only the import declarations and the `routes` array are understood.
+
+use crate::typescript::{self, apply_edits, is_own_line, line_end, line_number, line_start, Element, TokenKind, Tokens};
+
+
+/// `new Route("/path", ComponentClass)`
+struct Route {
+    path: Option<String>,
+    component: Option<String>,
+}
+
+impl Route {
+    fn read(tokens: &Tokens, element: &Element) -> Route {
+        let first_token = element.first_token;
+        let is_route = tokens.is_word(first_token, "new") && tokens.is_word(first_token + 1, "Route") && tokens.text(first_token + 2) == "(";
+        let path = (is_route && tokens.tokens.get(first_token + 3).is_some_and(|token| token.kind == TokenKind::String)).then(|| {
+            let path = tokens.text(first_token + 3);
+            path[1..path.len() - 1].to_string()
+        });
+        let component = (is_route && tokens.text(first_token + 4) == ",").then(|| tokens.text(first_token + 5).to_string());
+
+        Route { path, component }
+    }
+}
+
+/// Imports `class_name` from `import_path` and appends `new Route(route_path, class_name)` to the routes array
+pub fn add_component(source: &str, class_name: &str, import_path: &str, route_path: &str) -> Result<String, String> {
+    if !route_path.starts_with('/') || route_path.contains(['"', '\\']) {
+        return Err(format!("Invalid route path \"{}\". It has to start with /", route_path));
+    }
+
+    let tokens = Tokens::new(source)?;
+    let (open, close) = tokens.array("routes").ok_or("routes.ts does not contain the routes array")?;
+    let routes: Vec<Route> = tokens.array_elements(open, close).iter().map(|element| Route::read(&tokens, element)).collect();
+    if let Some(route) = routes.iter().find(|route| route.path.as_deref() == Some(route_path)) {
+        return Err(format!("The path \"{}\" is already routed to {}", route_path, route.component.as_deref().unwrap_or("another component")));
+    }
+
+    let mut edits = Vec::new();
+    let import_statement = format!("import {{ {} }} from \"{}\";", class_name, import_path);
+    edits.extend(typescript::import_edit(source, &tokens, class_name, import_path, &import_statement).map_err(|error| format!("routes.ts: {}", error))?);
+    edits.push(typescript::append_element_edit(source, &tokens, open, close, &format!("new Route(\"{}\", {})", route_path, class_name)));
+
+    Ok(apply_edits(source, edits))
+}
+
+/// Removes the routes to `class_name` and its import. Refuses if routes.ts would still reference the class afterwards.
+pub fn remove_component(source: &str, class_name: &str) -> Result<String, String> {
+    let tokens = Tokens::new(source)?;
+    let mut edits = Vec::new();
+
+    if let Some((open, close)) = tokens.array("routes") {
+        let elements = tokens.array_elements(open, close);
+        for (index, element) in elements.iter().enumerate() {
+            if Route::read(&tokens, element).component.as_deref() != Some(class_name) {
+                continue;
+            }
+
+            let first = tokens.tokens[element.first_token];
+            let has_comma = tokens.text(element.last_token + 1) == ",";
+            let last = tokens.tokens[if has_comma { element.last_token + 1 } else { element.last_token }];
+            let range = if is_own_line(source, first.start, last.end) {
+                line_start(source, first.start)..line_end(source, last.end)
+            } else if has_comma {
+                first.start..tokens.tokens[element.last_token + 2].start
+            } else if index == 0 {
+                first.start..last.end
+            } else {
+                // the last element of a single line array takes the comma in front of it
+                tokens.tokens[elements[index - 1].last_token].end..last.end
+            };
+            edits.push((range, String::new()));
+        }
+    }
+
+    for import in tokens.imports() {
+        let Some(position) = import.specifiers.iter().position(|specifier| specifier.local == class_name) else {
+            continue;
+        };
+
+        if import.specifiers.len() == 1 && !import.has_default {
+            let start = tokens.tokens[import.start_token].start;
+            let end = tokens.tokens[import.end_token].end;
+            let range = if is_own_line(source, start, end) { line_start(source, start)..line_end(source, end) } else { start..end };
+            edits.push((range, String::new()));
+        } else {
+            let specifier = &import.specifiers[position];
+            let range = if position + 1 < import.specifiers.len() {
+                tokens.tokens[specifier.first_token].start..tokens.tokens[import.specifiers[position + 1].first_token].start
+            } else if position > 0 {
+                tokens.tokens[import.specifiers[position - 1].last_token].end..tokens.tokens[specifier.last_token].end
+            } else {
+                // `import Default, { Class }`
+                tokens.tokens[specifier.first_token - 2].start..tokens.tokens[specifier.last_token + 1].end
+            };
+            edits.push((range, String::new()));
+        }
+    }
+
+    let new_source = apply_edits(source, edits);
+    let new_tokens = Tokens::new(&new_source)?;
+    let references: Vec<String> = (0..new_tokens.tokens.len())
+        .filter(|&index| new_tokens.is_word(index, class_name))
+        .map(|index| line_number(&new_source, new_tokens.tokens[index].start).to_string())
+        .collect();
+    if !references.is_empty() {
+        return Err(format!("routes.ts still references {} in line {}. Please remove these references first.", class_name, references.join(", ")));
+    }
+
+    Ok(new_source)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUTES: &str = "import { Route, Component } from \"../dependencies.ts\";\nimport { StartpageComponent } from \"./startpage/startpage.ts\";\n\n\nexport const routes: Route[] = [\n\tnew Route(\"/\", StartpageComponent),\n];\n\nexport const domain_to_route_selector = async () => routes;\n";
+
+    #[test]
+    fn test_add_and_remove_component() {
+        let added = add_component(ROUTES, "UsersComponent", "./users/users.ts", "/users").unwrap();
+        assert!(added.contains("import { StartpageComponent } from \"./startpage/startpage.ts\";\nimport { UsersComponent } from \"./users/users.ts\";\n\n"));
+        assert!(added.contains("\tnew Route(\"/\", StartpageComponent),\n\tnew Route(\"/users\", UsersComponent),\n];"));
+        assert_eq!(add_component(&added, "ListComponent", "./list/list.ts", "/users").unwrap_err(), "The path \"/users\" is already routed to UsersComponent");
+        assert_eq!(remove_component(&added, "UsersComponent").unwrap(), ROUTES);
+
+        // other formatting, comments and several specifiers in one import
+        let source = "import { StartpageComponent, UsersComponent } from './components.ts' // users\nexport const routes = [new Route('/', StartpageComponent), new Route(\"/users\", UsersComponent, { title: \"[Users]\" })];\n";
+        assert_eq!(remove_component(source, "UsersComponent").unwrap(), "import { StartpageComponent } from './components.ts' // users\nexport const routes = [new Route('/', StartpageComponent)];\n");
+        assert_eq!(add_component("export const routes: Route[] = [];\n", "UsersComponent", "./users/users.ts", "/users").unwrap(), "import { UsersComponent } from \"./users/users.ts\";\nexport const routes: Route[] = [\n\tnew Route(\"/users\", UsersComponent),\n];\n");
+
+        let referenced = ROUTES.replace("=> routes;", "=> [...routes, new Route(\"/start\", StartpageComponent)];");
+        assert_eq!(remove_component(&referenced, "StartpageComponent").unwrap_err(), "routes.ts still references StartpageComponent in line 7. Please remove these references first.");
+    }
+}
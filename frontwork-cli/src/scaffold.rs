@@ -0,0 +1,292 @@
+use std::{fs, path::{Component as PathComponent, Path, PathBuf}};
+use include_dir::{include_dir, Dir};
+use crate::config::FrontworkConfig;
+
+
+/// Templates of the project for the files generated by the CLI. Each directory in templates/{kind} is a template,
+/// e.g. templates/component/admin_page for `frontwork component new --template admin_page`.
+pub const TEMPLATES_DIR: &str = "templates";
+pub const DEFAULT_TEMPLATE: &str = "default";
+/// The default template of each kind, used if the project has no templates/{kind}/default
+static EMBEDDED_TEMPLATES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/");
+
+/// Renders the file names and contents of a template into `directory`
+pub fn render_template(config: &FrontworkConfig, kind: &str, template: Option<&str>, directory: &Path, placeholders: &[(&str, String)]) -> Result<Vec<(PathBuf, String)>, String> {
+    Ok(load_template(config, kind, template)?.into_iter()
+        .map(|(relative_path, content)| (directory.join(render(&relative_path, placeholders)), render(&content, placeholders)))
+        .collect())
+}
+
+/// The files of the template as paths relative to the generated directory with their content.
+/// Templates of the project are preferred over the embedded default.
+fn load_template(config: &FrontworkConfig, kind: &str, template: Option<&str>) -> Result<Vec<(String, String)>, String> {
+    let template = template.unwrap_or(DEFAULT_TEMPLATE);
+    if template.is_empty() || template.contains(['/', '\\']) || template.starts_with('.') {
+        return Err(format!("Invalid {} template name \"{}\"", kind, template));
+    }
+    let templates_path = Path::new(&config.path(TEMPLATES_DIR)).join(kind);
+    let template_path = templates_path.join(template);
+
+    if template_path.is_dir() {
+        let mut files = Vec::new();
+        read_template_dir(&template_path, &template_path, &mut files)?;
+        return Ok(files);
+    }
+    if let (DEFAULT_TEMPLATE, Some(embedded)) = (template, EMBEDDED_TEMPLATES.get_dir(kind)) {
+        return Ok(embedded.files()
+            .map(|file| (file.path().strip_prefix(kind).unwrap().to_string_lossy().to_string(), file.contents_utf8().unwrap_or_default().to_string()))
+            .collect());
+    }
+
+    let available: Vec<String> = fs::read_dir(&templates_path).into_iter().flatten().flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    Err(format!("The {} template \"{}\" does not exist in {}/{}. Available templates: {}", kind, template, TEMPLATES_DIR, kind, if available.is_empty() { DEFAULT_TEMPLATE.to_string() } else { available.join(", ") }))
+}
+
+fn read_template_dir(template_path: &Path, directory: &Path, files: &mut Vec<(String, String)>) -> Result<(), String> {
+    let entries = fs::read_dir(directory).map_err(|error| format!("Can not open \"{}\": {}", directory.display(), error))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            read_template_dir(template_path, &path, files)?;
+        } else {
+            let relative_path = path.strip_prefix(template_path).unwrap().to_string_lossy().to_string();
+            files.push((relative_path, read_file(&path)?));
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the placeholders like `{{class_name}}`
+pub fn render(text: &str, placeholders: &[(&str, String)]) -> String {
+    placeholders.iter().fold(text.to_string(), |text, (key, value)| text.replace(&format!("{{{{{}}}}}", key), value))
+}
+
+/// Creates the files and their directories. Existing files are not overwritten.
+pub fn write_files(files: &[(PathBuf, String)]) -> Result<(), String> {
+    if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+        return Err(format!("{} already exists", path.display()));
+    }
+
+    for (path, content) in files {
+        let directory = path.parent().unwrap();
+        fs::create_dir_all(directory).map_err(|error| format!("Unable to create {}: {}", directory.display(), error))?;
+        write_file(path, content)?;
+    }
+    Ok(())
+}
+
+/// The path of `file` for an import in a file of `from_directory`, e.g. `./users/users.ts` or `../../dependencies.ts`
+pub fn relative_import_path(from_directory: &Path, file: &Path) -> String {
+    let segments = |path: &Path| -> Vec<String> {
+        normalize(path).components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect()
+    };
+    let from = segments(from_directory);
+    let to = segments(file);
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut segments = vec![".."; from.len() - common];
+    if segments.is_empty() {
+        segments.push(".");
+    }
+    segments.extend(to[common..].iter().map(String::as_str));
+    segments.join("/")
+}
+
+/// Resolves `.` and `..` without accessing the file system
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            PathComponent::CurDir => {}
+            PathComponent::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// The files in `directory` and its subdirectories
+pub fn files_in(directory: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(directory).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(files_in(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+pub fn read_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|error| format!("Can not open \"{}\": {}", path.display(), error))
+}
+
+pub fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content).map_err(|error| format!("Unable to write \"{}\": {}", path.display(), error))
+}
+
+/// Changes to files that are undone by `rollback`, so that edits across several files are applied completely or not at all
+#[derive(Default)]
+pub struct FileTransaction {
+    undo: Vec<Undo>,
+}
+
+enum Undo {
+    /// Restores the content of a written file, or removes it if it did not exist
+    Restore { path: PathBuf, content: Option<Vec<u8>> },
+    Rename { from: PathBuf, to: PathBuf },
+    RemoveDir(PathBuf),
+    CreateDir(PathBuf),
+}
+
+impl FileTransaction {
+    /// Runs `step` and rolls back its changes if it fails. The error then lists the changes that could not be undone.
+    pub fn run<T>(step: impl FnOnce(&mut FileTransaction) -> Result<T, String>) -> Result<T, String> {
+        let mut transaction = FileTransaction::default();
+        step(&mut transaction).map_err(|error| {
+            let failures = transaction.rollback();
+            match failures.is_empty() {
+                true => error,
+                false => format!("{}\nUnable to roll back:\n{}", error, failures.join("\n")),
+            }
+        })
+    }
+
+    pub fn write(&mut self, path: &Path, content: &str) -> Result<(), String> {
+        self.create_dir_all(path.parent().unwrap())?;
+        let original = fs::read(path).ok();
+        write_file(path, content)?;
+        self.undo.push(Undo::Restore { path: path.to_path_buf(), content: original });
+        Ok(())
+    }
+
+    pub fn rename(&mut self, from: &Path, to: &Path) -> Result<(), String> {
+        if to.exists() {
+            return Err(format!("{} already exists", to.display()));
+        }
+        self.create_dir_all(to.parent().unwrap())?;
+        fs::rename(from, to).map_err(|error| format!("Unable to move \"{}\" to \"{}\": {}", from.display(), to.display(), error))?;
+        self.undo.push(Undo::Rename { from: from.to_path_buf(), to: to.to_path_buf() });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, path: &Path) -> Result<(), String> {
+        let original = fs::read(path).map_err(|error| format!("Can not open \"{}\": {}", path.display(), error))?;
+        fs::remove_file(path).map_err(|error| format!("Unable to remove \"{}\": {}", path.display(), error))?;
+        self.undo.push(Undo::Restore { path: path.to_path_buf(), content: Some(original) });
+        Ok(())
+    }
+
+    /// Removes an empty directory
+    pub fn remove_dir(&mut self, directory: &Path) -> Result<(), String> {
+        fs::remove_dir(directory).map_err(|error| format!("Unable to remove \"{}\": {}", directory.display(), error))?;
+        self.undo.push(Undo::CreateDir(directory.to_path_buf()));
+        Ok(())
+    }
+
+    fn create_dir_all(&mut self, directory: &Path) -> Result<(), String> {
+        let mut missing: Vec<&Path> = directory.ancestors().take_while(|ancestor| !ancestor.exists()).collect();
+        missing.reverse();
+        for directory in missing {
+            fs::create_dir(directory).map_err(|error| format!("Unable to create {}: {}", directory.display(), error))?;
+            self.undo.push(Undo::RemoveDir(directory.to_path_buf()));
+        }
+        Ok(())
+    }
+
+    /// Undoes all changes in reverse order. Returns the changes that could not be undone.
+    pub fn rollback(self) -> Vec<String> {
+        let mut failures = Vec::new();
+        for undo in self.undo.into_iter().rev() {
+            let result = match &undo {
+                Undo::Restore { path, content: Some(content) } => fs::write(path, content),
+                Undo::Restore { path, content: None } => fs::remove_file(path),
+                Undo::Rename { from, to } => fs::rename(to, from),
+                Undo::RemoveDir(directory) => fs::remove_dir(directory),
+                Undo::CreateDir(directory) => fs::create_dir(directory),
+            };
+            if let Err(error) = result {
+                let path = match &undo {
+                    Undo::Restore { path, .. } | Undo::Rename { from: path, .. } | Undo::RemoveDir(path) | Undo::CreateDir(path) => path,
+                };
+                failures.push(format!("{}: {}", path.display(), error));
+            }
+        }
+        failures
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_templates() {
+        let project_path = TempDir::new("scaffold");
+        let config = FrontworkConfig { project_path: project_path.to_string_lossy().to_string(), ..Default::default() };
+        fs::create_dir_all(project_path.join("templates/component/page/styles")).unwrap();
+        fs::write(project_path.join("templates/component/page/{{snake_name}}.ts"), "export class {{class_name}} {} // {{path}}").unwrap();
+        fs::write(project_path.join("templates/component/page/styles/{{snake_name}}.scss"), "").unwrap();
+
+        let mut default_template = load_template(&config, "component", None).unwrap();
+        default_template.sort();
+        assert_eq!(default_template[0].0, "{{snake_name}}.scss");
+        assert!(default_template[1].1.contains("export class {{class_name}} implements Component {"));
+
+        let mut page_template = load_template(&config, "component", Some("page")).unwrap();
+        page_template.sort();
+        assert_eq!(page_template[1].0, "{{snake_name}}.ts");
+        assert_eq!(render(&page_template[1].1, &[("class_name", "ListComponent".to_string()), ("path", "admin/list".to_string())]), "export class ListComponent {} // admin/list");
+        assert_eq!(load_template(&config, "component", Some("dialog")).unwrap_err(), "The component template \"dialog\" does not exist in templates/component. Available templates: page");
+        assert_eq!(load_template(&config, "subservice", None).unwrap()[0].0, "{{snake_name}}.ts");
+    }
+
+    #[test]
+    fn test_relative_import_path() {
+        assert_eq!(relative_import_path(Path::new("/p/src/components"), Path::new("/p/src/components/admin/list/list.ts")), "./admin/list/list.ts");
+        assert_eq!(relative_import_path(Path::new("/p/src/components/admin/list"), Path::new("/p/src/dependencies.ts")), "../../../dependencies.ts");
+        assert_eq!(relative_import_path(Path::new("/p/./src"), Path::new("/p/src/components/list/list.scss")), "./components/list/list.scss");
+        assert_eq!(normalize(Path::new("/p/src/components/admin/../startpage/./startpage.ts")), Path::new("/p/src/components/startpage/startpage.ts"));
+    }
+
+    #[test]
+    fn test_file_transaction_rollback() {
+        let directory = TempDir::new("transaction");
+        fs::write(directory.join("routes.ts"), "old").unwrap();
+        fs::write(directory.join("list.ts"), "list").unwrap();
+        fs::create_dir_all(directory.join("users")).unwrap();
+        fs::write(directory.join("users/users.ts"), "users").unwrap();
+
+        let mut transaction = FileTransaction::default();
+        transaction.write(&directory.join("routes.ts"), "new").unwrap();
+        transaction.rename(&directory.join("list.ts"), &directory.join("admin/users/list.ts")).unwrap();
+        transaction.write(&directory.join("admin/users/list.scss"), "").unwrap();
+        transaction.remove(&directory.join("users/users.ts")).unwrap();
+        transaction.remove_dir(&directory.join("users")).unwrap();
+        assert!(transaction.rollback().is_empty());
+
+        assert_eq!(fs::read_to_string(directory.join("routes.ts")).unwrap(), "old");
+        assert_eq!(fs::read_to_string(directory.join("list.ts")).unwrap(), "list");
+        assert_eq!(fs::read_to_string(directory.join("users/users.ts")).unwrap(), "users");
+        assert!(!directory.join("admin").exists());
+
+        let error = FileTransaction::run(|transaction| {
+            transaction.write(&directory.join("routes.ts"), "new")?;
+            transaction.remove(&directory.join("missing.ts"))
+        }).unwrap_err();
+        assert!(error.starts_with("Can not open"), "{}", error);
+        assert_eq!(fs::read_to_string(directory.join("routes.ts")).unwrap(), "old");
+    }
+}
@@ -0,0 +1,122 @@
+use std::{env, fs, os::unix::fs::PermissionsExt, path::Path, process::Command};
+use anyhow::{bail, Context, Result};
+use semver::Version;
+use serde::Deserialize;
+use crate::{download::{self, DownloadOptions}, host};
+
+
+const LATEST_RELEASE_API_URL: &str = "https://api.github.com/repos/frontwork-org/frontwork/releases/latest";
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+    /// "sha256:{hash}"; set by GitHub for assets uploaded since 2025
+    digest: Option<String>,
+}
+
+/// Replaces the running executable with the latest release of frontwork.
+/// Returns the installed version, or None if this is already the latest one.
+pub async fn self_update() -> Result<Option<Version>> {
+    let current_version = Version::parse(env!("CARGO_PKG_VERSION"))?;
+    let release: Release = download::http_client()?.get(LATEST_RELEASE_API_URL).send().await?
+        .error_for_status()?
+        .json().await
+        .context("Unable to read the latest frontwork release")?;
+    let latest_version = Version::parse(release.tag_name.trim_start_matches('v'))
+        .with_context(|| format!("The latest frontwork release has the invalid tag {}", release.tag_name))?;
+    if latest_version <= current_version {
+        return Ok(None);
+    }
+
+    // the linux x86_64 binary has been published as plain "frontwork"
+    let target = host::deno_target().map_err(anyhow::Error::msg)?;
+    let asset_names = [format!("frontwork-{}", target), if target == "x86_64-unknown-linux-gnu" { "frontwork".to_string() } else { String::new() }];
+    let Some(asset) = release.assets.iter().find(|asset| asset_names.contains(&asset.name)) else {
+        bail!("frontwork {} has no binary for {}. Please update with 'cargo install frontwork'.", latest_version, target);
+    };
+
+    let mut options = DownloadOptions::default();
+    options.expected_sha256 = Some(asset_sha256(&release, asset, &options).await?);
+    let download = download::download_large_file(&asset.browser_download_url, &options).await?;
+
+    let new_executable_version = executable_version(&download.path)?;
+    if new_executable_version != latest_version {
+        bail!("The downloaded binary reports version {}, but {} was expected", new_executable_version, latest_version);
+    }
+
+    let current_executable = env::current_exe()?.canonicalize()?;
+    replace_executable(&download.path, &current_executable)
+        .with_context(|| format!("Unable to replace {}", current_executable.display()))?;
+    fs::remove_file(&download.path).ok();
+
+    Ok(Some(latest_version))
+}
+
+/// The digest of the asset, otherwise the hash of a published `{asset}.sha256sum`. Unverified binaries are refused.
+async fn asset_sha256(release: &Release, asset: &ReleaseAsset, options: &DownloadOptions) -> Result<String> {
+    if let Some(sha256) = asset.digest.as_deref().and_then(|digest| digest.strip_prefix("sha256:")) {
+        return Ok(sha256.to_lowercase());
+    }
+
+    let checksum_name = format!("{}.sha256sum", asset.name);
+    let Some(checksum_asset) = release.assets.iter().find(|asset| asset.name == checksum_name) else {
+        bail!("The release has no SHA-256 sum for {}; refusing to install an unverified binary", asset.name);
+    };
+    let content = download::fetch_text(&checksum_asset.browser_download_url, options).await?;
+    download::parse_sha256sum(&content).with_context(|| format!("{} contains no SHA-256 sum", checksum_name))
+}
+
+fn executable_version(path: &Path) -> Result<Version> {
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    let output = Command::new(path).arg("--version").output()
+        .with_context(|| format!("Unable to run {}", path.display()))?;
+    // prints: Frontwork CLI Tool v0.4.4 by ...
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.split_whitespace()
+        .find_map(|word| word.strip_prefix('v').and_then(|version| Version::parse(version).ok()))
+        .with_context(|| format!("Unable to read the version of {}", path.display()))
+}
+
+/// Copies the new executable next to the current one and renames it over it, so that the
+/// current executable is replaced at once and stays intact if anything fails before.
+fn replace_executable(new_executable: &Path, current_executable: &Path) -> std::io::Result<()> {
+    let file_name = current_executable.file_name().unwrap().to_string_lossy();
+    let staging_path = current_executable.with_file_name(format!(".{}.new-{}", file_name, std::process::id()));
+
+    let result = fs::copy(new_executable, &staging_path)
+        .and_then(|_| fs::set_permissions(&staging_path, fs::Permissions::from_mode(0o755)))
+        .and_then(|_| fs::rename(&staging_path, current_executable));
+    if result.is_err() {
+        fs::remove_file(&staging_path).ok();
+    }
+    result
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_replace_executable() {
+        let dir = TempDir::new("self-update");
+        let current_executable = dir.join("frontwork");
+        let new_executable = dir.join("download");
+        fs::write(&current_executable, "old").unwrap();
+        fs::write(&new_executable, "#!/bin/sh\necho Frontwork CLI Tool v9.1.0 by LuceusXylian\n").unwrap();
+
+        assert_eq!(executable_version(&new_executable).unwrap(), Version::new(9, 1, 0));
+        replace_executable(&new_executable, &current_executable).unwrap();
+        assert_eq!(fs::read(&current_executable).unwrap(), fs::read(&new_executable).unwrap());
+        assert_eq!(fs::metadata(&current_executable).unwrap().permissions().mode() & 0o777, 0o755);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+    }
+}
@@ -0,0 +1,156 @@
+use std::{env, fs, io, path::{Path, PathBuf}};
+
+
+/// Surround the lines `frontwork install` adds to the shell profiles, so that `frontwork uninstall` can remove them again
+pub const PROFILE_BLOCK_START: &str = "# >>> frontwork deno >>>";
+pub const PROFILE_BLOCK_END: &str = "# <<< frontwork deno <<<";
+
+#[derive(Debug, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    /// sh, dash, ksh or an unknown shell; they read ~/.profile on login
+    Posix,
+}
+
+impl Shell {
+    /// The login shell of the user from $SHELL
+    pub fn from_env() -> Shell {
+        Self::from_path(&env::var("SHELL").unwrap_or_default())
+    }
+
+    fn from_path(shell_path: &str) -> Shell {
+        match Path::new(shell_path).file_name().and_then(|name| name.to_str()) {
+            Some("bash") => Shell::Bash,
+            Some("zsh") => Shell::Zsh,
+            Some("fish") => Shell::Fish,
+            _ => Shell::Posix,
+        }
+    }
+
+    /// The files read by interactive and login shells
+    pub fn profile_paths(&self, home_path: &Path) -> Vec<PathBuf> {
+        match self {
+            Shell::Bash => {
+                // bash reads ~/.profile on login only if there is no ~/.bash_profile
+                let login_profile = if home_path.join(".bash_profile").exists() { ".bash_profile" } else { ".profile" };
+                vec![home_path.join(".bashrc"), home_path.join(login_profile)]
+            }
+            Shell::Zsh => {
+                let zdotdir = env::var("ZDOTDIR").map(PathBuf::from).unwrap_or(home_path.to_path_buf());
+                vec![zdotdir.join(".zshrc")]
+            }
+            Shell::Fish => vec![home_path.join(".config/fish/config.fish")],
+            Shell::Posix => vec![home_path.join(".profile")],
+        }
+    }
+
+    /// Lines that put `{deno_install}/bin` on PATH
+    pub fn export_lines(&self, deno_install: &str) -> String {
+        match self {
+            Shell::Fish => format!("set -gx DENO_INSTALL \"{}\"\nfish_add_path \"$DENO_INSTALL/bin\"\n", deno_install),
+            _ => format!("export DENO_INSTALL=\"{}\"\nexport PATH=\"$DENO_INSTALL/bin:$PATH\"\n", deno_install),
+        }
+    }
+
+    /// Appends the export lines to every profile that does not set DENO_INSTALL yet. Returns the modified files.
+    pub fn add_to_path(&self, home_path: &Path, deno_install: &str) -> io::Result<Vec<PathBuf>> {
+        let mut modified = Vec::new();
+
+        for profile_path in self.profile_paths(home_path) {
+            let content = fs::read_to_string(&profile_path).unwrap_or_default();
+            if content.contains("DENO_INSTALL") {
+                continue;
+            }
+
+            if let Some(parent) = profile_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut new_content = content;
+            if !new_content.is_empty() && !new_content.ends_with('\n') {
+                new_content.push('\n');
+            }
+            new_content += &format!("\n{}\n{}{}\n", PROFILE_BLOCK_START, self.export_lines(deno_install), PROFILE_BLOCK_END);
+            fs::write(&profile_path, new_content)?;
+            modified.push(profile_path);
+        }
+
+        Ok(modified)
+    }
+}
+
+/// Removes the lines `frontwork install` added from the profiles of all shells. Returns the modified files.
+pub fn remove_from_profiles(home_path: &Path, deno_install: &str) -> io::Result<Vec<PathBuf>> {
+    let mut profile_paths: Vec<PathBuf> = [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::Posix].iter()
+        .flat_map(|shell| shell.profile_paths(home_path))
+        .collect();
+    profile_paths.push(home_path.join(".bash_profile"));
+    profile_paths.sort();
+    profile_paths.dedup();
+
+    // versions before the block markers appended these lines to ~/.bashrc
+    let legacy_lines = format!("\n\nexport DENO_INSTALL=\"{}\"\nexport PATH=\"$DENO_INSTALL/bin:$PATH\"\n", deno_install);
+    let mut modified = Vec::new();
+
+    for profile_path in profile_paths {
+        let Ok(content) = fs::read_to_string(&profile_path) else {
+            continue;
+        };
+
+        let mut new_content = content.replace(&legacy_lines, "\n");
+        while let (Some(start), Some(end)) = (new_content.find(PROFILE_BLOCK_START), new_content.find(PROFILE_BLOCK_END)) {
+            if end < start {
+                break;
+            }
+            // the block and the empty line in front of it
+            let before = &new_content[..start];
+            let start = if before.ends_with("\n\n") || before == "\n" { start - 1 } else { start };
+            let end = (end + PROFILE_BLOCK_END.len() + 1).min(new_content.len());
+            new_content.replace_range(start..end, "");
+        }
+
+        if new_content != content {
+            fs::write(&profile_path, new_content)?;
+            modified.push(profile_path);
+        }
+    }
+
+    Ok(modified)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_add_to_path() {
+        let home_path = TempDir::new("shell");
+        fs::write(home_path.join(".bashrc"), "alias ll='ls -l'").unwrap();
+
+        assert_eq!(Shell::from_path("/usr/bin/fish"), Shell::Fish);
+        assert_eq!(Shell::from_path(""), Shell::Posix);
+
+        let modified = Shell::Bash.add_to_path(&home_path, "/home/user/.deno").unwrap();
+        assert_eq!(modified, [home_path.join(".bashrc"), home_path.join(".profile")]);
+        let bashrc = fs::read_to_string(home_path.join(".bashrc")).unwrap();
+        assert!(bashrc.starts_with("alias ll='ls -l'\n\n# >>> frontwork deno >>>\nexport DENO_INSTALL=\"/home/user/.deno\"\n"));
+        // nothing is added twice
+        assert!(Shell::Bash.add_to_path(&home_path, "/home/user/.deno").unwrap().is_empty());
+
+        Shell::Fish.add_to_path(&home_path, "/home/user/.deno").unwrap();
+        assert!(fs::read_to_string(home_path.join(".config/fish/config.fish")).unwrap().contains("fish_add_path \"$DENO_INSTALL/bin\""));
+
+        let removed = remove_from_profiles(&home_path, "/home/user/.deno").unwrap();
+        assert_eq!(removed.len(), 3);
+        assert_eq!(fs::read_to_string(home_path.join(".bashrc")).unwrap(), "alias ll='ls -l'\n");
+        assert_eq!(fs::read_to_string(home_path.join(".profile")).unwrap(), "");
+
+        // lines of older versions without the block markers
+        fs::write(home_path.join(".bashrc"), "alias ll='ls -l'\n\nexport DENO_INSTALL=\"/home/user/.deno\"\nexport PATH=\"$DENO_INSTALL/bin:$PATH\"\n").unwrap();
+        remove_from_profiles(&home_path, "/home/user/.deno").unwrap();
+        assert_eq!(fs::read_to_string(home_path.join(".bashrc")).unwrap(), "alias ll='ls -l'\n");
+    }
+}
@@ -0,0 +1,226 @@
+use std::io::{self, BufRead, BufReader, Read};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+
+const BACKOFF_INITIAL: Duration = Duration::from_millis(500);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// A process that ran at least this long before exiting is restarted without backoff
+const BACKOFF_RESET_AFTER: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// Process group ids of every child spawned through `spawn_prefixed` that has not been killed yet
+static RUNNING_PROCESS_GROUPS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Kills every running child process tree and exits when the user hits Ctrl-C.
+pub fn install_ctrlc_handler() {
+    ctrlc::set_handler(|| {
+        let process_groups = RUNNING_PROCESS_GROUPS.lock().map(|groups| groups.clone()).unwrap_or_default();
+        for pgid in process_groups {
+            signal_process_group(pgid, libc::SIGTERM);
+        }
+        std::process::exit(130);
+    })
+    .expect("Unable to set Ctrl-C handler");
+}
+
+/// Spawns the command in its own process group and forwards its stdout and stderr line by line,
+/// prefixed with `[prefix]`.
+pub fn spawn_prefixed(command: &mut Command, prefix: &'static str) -> io::Result<Child> {
+    let mut child = command
+        .process_group(0)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    RUNNING_PROCESS_GROUPS.lock().unwrap().push(child.id());
+
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, prefix, false);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, prefix, true);
+    }
+
+    Ok(child)
+}
+
+fn forward_lines(stream: impl Read + Send + 'static, prefix: &'static str, is_stderr: bool) {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if is_stderr {
+                eprintln!("[{}] {}", prefix, line);
+            } else {
+                println!("[{}] {}", prefix, line);
+            }
+        }
+    });
+}
+
+/// Waits for a child spawned through `spawn_prefixed` and forgets about its process group.
+pub fn wait(child: &mut Child) -> io::Result<std::process::ExitStatus> {
+    let status = child.wait();
+    unregister(child.id());
+    status
+}
+
+/// Terminates the whole process tree of a child spawned through `spawn_prefixed`.
+/// Sends SIGTERM first and SIGKILL if the process is still alive after a grace period.
+pub fn kill_process_tree(child: &mut Child) {
+    let pgid = child.id();
+    signal_process_group(pgid, libc::SIGTERM);
+
+    let started = Instant::now();
+    while started.elapsed() < KILL_GRACE_PERIOD {
+        if let Ok(Some(_)) = child.try_wait() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    // the group may still contain grandchildren even if the direct child is gone
+    signal_process_group(pgid, libc::SIGKILL);
+    child.wait().ok();
+    unregister(pgid);
+}
+
+fn signal_process_group(pgid: u32, signal: libc::c_int) {
+    unsafe {
+        libc::kill(-(pgid as libc::pid_t), signal);
+    }
+}
+
+fn unregister(pgid: u32) {
+    if let Ok(mut groups) = RUNNING_PROCESS_GROUPS.lock() {
+        groups.retain(|&id| id != pgid);
+    }
+}
+
+
+enum Message {
+    Restart,
+    Stop,
+}
+
+/// Keeps a long running process alive: restarts it with an increasing backoff when it exits
+/// and restarts it immediately on request.
+pub struct Supervisor {
+    sender: Sender<Message>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Supervisor {
+    pub fn start<F>(prefix: &'static str, create_command: F) -> Self
+    where
+        F: Fn() -> Command + Send + 'static,
+    {
+        let (sender, receiver) = channel();
+
+        let thread = thread::spawn(move || {
+            let spawn = || match spawn_prefixed(&mut create_command(), prefix) {
+                Ok(child) => Some((child, Instant::now())),
+                Err(error) => {
+                    eprintln!("[{}] failed to start: {}", prefix, error);
+                    None
+                }
+            };
+
+            let mut running = spawn();
+            let mut backoff = BACKOFF_INITIAL;
+
+            loop {
+                match receiver.recv_timeout(POLL_INTERVAL) {
+                    Ok(Message::Restart) => {
+                        if let Some((mut child, _)) = running.take() {
+                            kill_process_tree(&mut child);
+                        }
+                        backoff = BACKOFF_INITIAL;
+                        running = spawn();
+                    }
+                    Ok(Message::Stop) | Err(RecvTimeoutError::Disconnected) => {
+                        if let Some((mut child, _)) = running.take() {
+                            kill_process_tree(&mut child);
+                        }
+                        return;
+                    }
+                    Err(RecvTimeoutError::Timeout) => {
+                        let exit_status = match running.as_mut() {
+                            Some((child, _)) => child.try_wait().ok().flatten(),
+                            None => None,
+                        };
+
+                        if let Some(exit_status) = exit_status {
+                            let (mut child, started) = running.take().unwrap();
+                            unregister(child.id());
+                            child.wait().ok();
+
+                            if started.elapsed() >= BACKOFF_RESET_AFTER {
+                                backoff = BACKOFF_INITIAL;
+                            }
+                            eprintln!("[{}] exited with {}; restarting in {:?}", prefix, exit_status, backoff);
+
+                            // a restart request during the backoff starts the process right away
+                            match receiver.recv_timeout(backoff) {
+                                Ok(Message::Stop) | Err(RecvTimeoutError::Disconnected) => return,
+                                Ok(Message::Restart) => backoff = BACKOFF_INITIAL,
+                                Err(RecvTimeoutError::Timeout) => backoff = (backoff * 2).min(BACKOFF_MAX),
+                            }
+                            running = spawn();
+                        } else if running.is_none() {
+                            // spawning failed, try again after the backoff
+                            thread::sleep(backoff);
+                            backoff = (backoff * 2).min(BACKOFF_MAX);
+                            running = spawn();
+                        }
+                    }
+                }
+            }
+        });
+
+        Self { sender, thread: Some(thread) }
+    }
+
+    pub fn restart(&self) {
+        self.sender.send(Message::Restart).ok();
+    }
+}
+
+impl Drop for Supervisor {
+    fn drop(&mut self) {
+        self.sender.send(Message::Stop).ok();
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_restarts_crashed_process() {
+        let temp_dir = TempDir::new("supervisor");
+        let counter_file = temp_dir.join("counter");
+
+        let counter_file_arg = counter_file.clone();
+        let supervisor = Supervisor::start("test", move || {
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(format!("echo started >> {}; exit 1", counter_file_arg.display()));
+            command
+        });
+
+        thread::sleep(BACKOFF_INITIAL + POLL_INTERVAL * 4);
+        drop(supervisor);
+
+        let starts = std::fs::read_to_string(&counter_file).unwrap_or_default().lines().count();
+        assert!(starts >= 2, "process was started {} times", starts);
+    }
+}
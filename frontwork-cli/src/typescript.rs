@@ -0,0 +1,314 @@
+//! A scanner for TypeScript sources, which is just good enough to edit imports and array literals
+//! without being confused by formatting, comments and strings.
+
+use std::ops::Range;
+
+
+/// Replaces the range of the source with the string
+pub type Edit = (Range<usize>, String);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TokenKind {
+    /// Identifiers, keywords and numbers
+    Word,
+    /// String and template literals including their quotes
+    String,
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub struct Tokens<'a> {
+    source: &'a str,
+    pub tokens: Vec<Token>,
+}
+
+impl<'a> Tokens<'a> {
+    pub fn new(source: &'a str) -> Result<Tokens<'a>, String> {
+        let bytes = source.as_bytes();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let c = bytes[i];
+            if c.is_ascii_whitespace() {
+                i += 1;
+            } else if source[i..].starts_with("//") {
+                i = source[i..].find('\n').map_or(bytes.len(), |end| i + end);
+            } else if source[i..].starts_with("/*") {
+                let end = source[i + 2..].find("*/").ok_or_else(|| format!("Unterminated comment in line {}", line_number(source, i)))?;
+                i += end + 4;
+            } else if c == b'"' || c == b'\'' || c == b'`' {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != c {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                if i >= bytes.len() {
+                    return Err(format!("Unterminated string in line {}", line_number(source, start)));
+                }
+                i += 1;
+                tokens.push(Token { kind: TokenKind::String, start, end: i });
+            } else if c.is_ascii_alphanumeric() || c == b'_' || c == b'$' || c >= 0x80 {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'$' || bytes[i] >= 0x80) {
+                    i += 1;
+                }
+                tokens.push(Token { kind: TokenKind::Word, start, end: i });
+            } else {
+                tokens.push(Token { kind: TokenKind::Punct, start: i, end: i + 1 });
+                i += 1;
+            }
+        }
+
+        Ok(Tokens { source, tokens })
+    }
+
+    pub fn text(&self, index: usize) -> &'a str {
+        self.tokens.get(index).map_or("", |token| &self.source[token.start..token.end])
+    }
+
+    pub fn is_word(&self, index: usize, word: &str) -> bool {
+        self.tokens.get(index).is_some_and(|token| token.kind == TokenKind::Word) && self.text(index) == word
+    }
+
+    /// The index of the bracket that closes the one at `open`
+    pub fn closing_bracket(&self, open: usize) -> Option<usize> {
+        let mut depth = 0;
+        for index in open..self.tokens.len() {
+            match self.text(index) {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(index);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Top level import declarations
+    pub fn imports(&self) -> Vec<Import> {
+        let mut imports = Vec::new();
+        let mut depth = 0;
+        let mut index = 0;
+
+        while index < self.tokens.len() {
+            match self.text(index) {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                _ if depth == 0 && self.is_word(index, "import") && index + 1 < self.tokens.len() && self.text(index + 1) != "(" => {
+                    if let Some(import) = self.import_at(index) {
+                        index = import.end_token;
+                        imports.push(import);
+                    }
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+
+        imports
+    }
+
+    /// The string tokens of all module specifiers: `import ... from "x"`, `export ... from "x"`, `import "x"` and `import("x")`
+    pub fn module_specifiers(&self) -> Vec<usize> {
+        (1..self.tokens.len())
+            .filter(|&index| self.tokens[index].kind == TokenKind::String)
+            .filter(|&index| self.is_word(index - 1, "from") || self.is_word(index - 1, "import") || (index > 1 && self.text(index - 1) == "(" && self.is_word(index - 2, "import")))
+            .collect()
+    }
+
+    /// Parses `import Default, { A, B as C } from "module";` beginning at the `import` keyword
+    fn import_at(&self, import_token: usize) -> Option<Import> {
+        let mut specifiers = Vec::new();
+        let mut has_default = false;
+        let mut index = import_token + 1;
+        if self.is_word(index, "type") && !self.is_word(index + 1, "from") {
+            index += 1;
+        }
+
+        loop {
+            match self.text(index) {
+                "{" => {
+                    let close = self.closing_bracket(index)?;
+                    let mut specifier_start = index + 1;
+                    for separator in (index + 1..=close).filter(|&i| self.text(i) == "," || i == close) {
+                        if separator > specifier_start {
+                            // the local name is the last word: `A`, `type A` or `B as C`
+                            specifiers.push(Specifier { local: self.text(separator - 1).to_string(), first_token: specifier_start, last_token: separator - 1 });
+                        }
+                        specifier_start = separator + 1;
+                    }
+                    index = close + 1;
+                }
+                "from" => break,
+                "," => index += 1,
+                "*" => {
+                    has_default = true;
+                    index += 3;
+                }
+                _ if self.tokens.get(index)?.kind == TokenKind::Word => {
+                    has_default = true;
+                    index += 1;
+                }
+                // side effect imports like `import "module";` have nothing to edit
+                _ => return None,
+            }
+        }
+
+        let module_token = index + 1;
+        if self.tokens.get(module_token)?.kind != TokenKind::String {
+            return None;
+        }
+        let module = self.text(module_token);
+        let end_token = if self.text(module_token + 1) == ";" { module_token + 1 } else { module_token };
+
+        Some(Import {
+            start_token: import_token,
+            end_token,
+            module: module[1..module.len() - 1].to_string(),
+            specifiers,
+            has_default,
+        })
+    }
+
+    /// The `[` and `]` of the array assigned to a variable, e.g. `export const routes: Route[] = [ ... ];`
+    pub fn array(&self, name: &str) -> Option<(usize, usize)> {
+        let name = (1..self.tokens.len()).find(|&index| {
+            self.is_word(index, name) && ["const", "let", "var"].iter().any(|keyword| self.is_word(index - 1, keyword))
+        })?;
+        let assignment = (name..self.tokens.len()).find(|&index| self.text(index) == "=")?;
+        if self.text(assignment + 1) != "[" {
+            return None;
+        }
+        Some((assignment + 1, self.closing_bracket(assignment + 1)?))
+    }
+
+    /// The elements of the array between the brackets `open` and `close`
+    pub fn array_elements(&self, open: usize, close: usize) -> Vec<Element> {
+        let mut elements = Vec::new();
+        let mut element_start = open + 1;
+        let mut index = open + 1;
+
+        while index <= close {
+            if index == close || self.text(index) == "," {
+                if index > element_start {
+                    elements.push(Element { first_token: element_start, last_token: index - 1 });
+                }
+                element_start = index + 1;
+            } else if matches!(self.text(index), "(" | "[" | "{") {
+                index = self.closing_bracket(index).unwrap_or(close);
+                continue;
+            }
+            index += 1;
+        }
+
+        elements
+    }
+}
+
+pub struct Import {
+    pub start_token: usize,
+    pub end_token: usize,
+    pub module: String,
+    pub specifiers: Vec<Specifier>,
+    /// `import Default from` or `import * as Namespace from`
+    pub has_default: bool,
+}
+
+pub struct Specifier {
+    pub local: String,
+    pub first_token: usize,
+    pub last_token: usize,
+}
+
+/// An element of an array literal
+pub struct Element {
+    pub first_token: usize,
+    pub last_token: usize,
+}
+
+/// The edit that imports `local` with `statement` below the last import, or None if `local` is already imported from `module`.
+/// If `module` is imported with braces already, `local` is added to them instead.
+pub fn import_edit(source: &str, tokens: &Tokens, local: &str, module: &str, statement: &str) -> Result<Option<Edit>, String> {
+    let imports = tokens.imports();
+    match imports.iter().find(|import| import.specifiers.iter().any(|specifier| specifier.local == local)) {
+        Some(import) if import.module == module => Ok(None),
+        Some(import) => Err(format!("{} is already imported from \"{}\"", local, import.module)),
+        None => {
+            // not into `import type { ... }`, which would make `local` a type only import
+            let mut value_imports = imports.iter().filter(|import| import.module == module && !tokens.is_word(import.start_token + 1, "type"));
+            if let Some(specifier) = value_imports.find_map(|import| import.specifiers.last()) {
+                let position = tokens.tokens[specifier.last_token].end;
+                return Ok(Some((position..position, format!(", {}", local))));
+            }
+
+            // below the last import, otherwise at the beginning of the file
+            let position = imports.last().map_or(0, |import| line_end(source, tokens.tokens[import.end_token].end));
+            Ok(Some((position..position, format!("{}\n", statement))))
+        }
+    }
+}
+
+/// The edit that appends `element` to the array between the brackets `open` and `close` on its own line
+pub fn append_element_edit(source: &str, tokens: &Tokens, open: usize, close: usize, element: &str) -> Edit {
+    match tokens.array_elements(open, close).last() {
+        Some(last) => {
+            let indent = line_indent(source, tokens.tokens[last.first_token].start);
+            if tokens.text(last.last_token + 1) == "," {
+                let position = tokens.tokens[last.last_token + 1].end;
+                (position..position, format!("\n{}{},", indent, element))
+            } else {
+                let position = tokens.tokens[last.last_token].end;
+                (position..position, format!(",\n{}{}", indent, element))
+            }
+        }
+        None => {
+            let indent = line_indent(source, tokens.tokens[open].start);
+            (tokens.tokens[open].end..tokens.tokens[close].start, format!("\n{}\t{},\n{}", indent, element, indent))
+        }
+    }
+}
+
+/// Applies non-overlapping edits
+pub fn apply_edits(source: &str, mut edits: Vec<Edit>) -> String {
+    let mut result = source.to_string();
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    for (range, replacement) in edits {
+        result.replace_range(range, &replacement);
+    }
+    result
+}
+
+pub fn line_start(source: &str, position: usize) -> usize {
+    source[..position].rfind('\n').map_or(0, |index| index + 1)
+}
+
+/// The position after the newline that ends the line of `position`
+pub fn line_end(source: &str, position: usize) -> usize {
+    source[position..].find('\n').map_or(source.len(), |index| position + index + 1)
+}
+
+pub fn line_indent(source: &str, position: usize) -> &str {
+    let start = line_start(source, position);
+    let line = &source[start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Whether only whitespace surrounds `start..end` on its lines
+pub fn is_own_line(source: &str, start: usize, end: usize) -> bool {
+    source[line_start(source, start)..start].trim().is_empty() && source[end..line_end(source, end)].trim().is_empty()
+}
+
+pub fn line_number(source: &str, position: usize) -> usize {
+    source[..position].matches('\n').count() + 1
+}
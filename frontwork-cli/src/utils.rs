@@ -1,24 +1,10 @@
 #![allow(dead_code)]
-use std::{fs::File, io::{stdout, Write}, os::unix::fs::PermissionsExt, path::{Path, PathBuf}, process::Child, time::SystemTime};
+use std::{fs::File, io::{stdout, Write}, os::unix::fs::PermissionsExt, path::{Path, PathBuf}, process::Child};
 use std::fs;
 use rsass::{compile_scss_path, output};
 use zip::{result::ZipResult, ZipArchive};
 
 
-pub fn find_optional_arg(args: &[String], find: &str) -> Option<String> {
-    let args_len = args.len();
-    if args_len > 3 {
-        for i in 2..args_len {
-            if args[i] == find {
-                return if i+1 < args_len { Some(args[i+1].clone()) } else { None };
-            }
-        }
-    }
-
-    None
-}
-
-
 pub fn read_from_line(message: &str) -> String {
     print!("\n{}", message);
     stdout().flush().unwrap();
@@ -38,7 +24,7 @@ pub fn run_command(command: String) -> Child {
 pub fn create_dir_all_verbose(path: &String) {
     let dist_path = Path::new(&path);
     if !dist_path.exists() {
-        fs::create_dir_all(&dist_path).unwrap();
+        fs::create_dir_all(dist_path).unwrap();
     }
 }
 
@@ -61,69 +47,35 @@ fn request(url: &str) -> Result<reqwest::blocking::Response, ()> {
 }
 */
 
-pub fn rsync(src: String, dest: String) {
+pub fn rsync(src: String, dest: String) -> Result<(), String> {
     let console_info = rusync::ConsoleProgressInfo::new();
     // or any struct that implements the ProgressInfo trait
     let options = rusync::SyncOptions::default();
     let source = std::path::Path::new(&src);
     let destination = std::path::Path::new(&dest);
-    let syncer = rusync::Syncer::new(&source, &destination, options, Box::new(console_info));
+    let syncer = rusync::Syncer::new(source, destination, options, Box::new(console_info));
     let stats = syncer.sync();
     match stats {
-        Err(err) => {
-            eprintln!("Error when syncing: {}", err);
-        }
+        Err(err) => Err(format!("Error when syncing: {}", err)),
         Ok(stats) => {
             println!("Transfered {} files", stats.copied);
+            Ok(())
         }
     }
 }
 
-pub fn sass(src: String, dest: String) {
+pub fn sass(src: String, dest: String) -> Result<(), String> {
     let format = output::Format {
         style: output::Style::Compressed,
         .. Default::default()
     };
     match compile_scss_path(src.as_ref(), format) {
-        Ok(css) => {
-            match fs::write(Path::new(&dest), css) {
-                Ok(f) => f,
-                Err(error) => panic!("can not write css file to {:?}. Error: {:?}", dest, error)
-            }
-        },
-        Err(error) => println!("Unable to compile sass: {}", error),
+        Ok(css) => fs::write(Path::new(&dest), css)
+            .map_err(|error| format!("can not write css file to {:?}. Error: {:?}", dest, error)),
+        Err(error) => Err(format!("Unable to compile sass: {}", error)),
     }
 }
 
-pub struct TransversedFile {
-    pub path: String,
-    pub modified: SystemTime,
-}
-
-pub fn transverse_directory(dir_path: &Path) -> Vec<TransversedFile> {
-    let mut files: Vec<TransversedFile> = Vec::new();
-
-    fs::read_dir(dir_path).unwrap().for_each(|entry| {
-        let file = entry.unwrap();
-        let path = file.path();
-
-        let metadata = file.metadata().unwrap();
-
-        if metadata.is_file() {
-            files.push(TransversedFile {
-                path: path.as_os_str().to_str().unwrap().to_string(),
-                modified: metadata.modified().unwrap()
-            });
-        } else {
-            for file in transverse_directory(&path) {
-                files.push(file);
-            }
-        }
-    });
-
-    files
-}
-
 pub fn make_file_executable(file_path: &str) -> std::result::Result<(), std::io::Error> {
     fs::set_permissions(file_path, fs::Permissions::from_mode(0o775))
 }
@@ -135,6 +87,47 @@ pub fn zip_extract(archive_file: &PathBuf, target_dir: &PathBuf) -> ZipResult<()
     archive.extract(target_dir)
 }
 
+/// A directory of a test below the system temp directory. The name is unique across the tests of the process;
+/// the directory is removed on drop, also if an assertion fails.
+#[cfg(test)]
+pub struct TempDir(PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+    pub fn new(name: &str) -> TempDir {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let index = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("frontwork-{}-test-{}-{}", name, std::process::id(), index));
+        // left behind by a killed test run of a process with the same id
+        fs::remove_dir_all(&path).ok();
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.0).ok();
+    }
+}
+
 // pub fn move_file(from_filepath: &String, to_filepath: &String) {
 //     if std::fs::rename(&from_filepath, to_filepath).is_err()  {
 //         // failed to rename, the file could be on another disk, so we copy and then delete it
@@ -0,0 +1,203 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{EventKind, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, DebouncedEvent, Debouncer, RecommendedCache};
+
+
+#[derive(Debug, PartialEq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+    Renamed { from: PathBuf },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub path: PathBuf,
+    pub kind: ChangeKind,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ChangeKind::Created => write!(f, "created  {}", self.path.display()),
+            ChangeKind::Modified => write!(f, "modified {}", self.path.display()),
+            ChangeKind::Removed => write!(f, "removed  {}", self.path.display()),
+            ChangeKind::Renamed { from } => write!(f, "renamed  {} -> {}", from.display(), self.path.display()),
+        }
+    }
+}
+
+/// Watches directories recursively through the native file system notifications (inotify on linux)
+/// and collects the events of a burst of writes into one list of changes.
+pub struct SourceWatcher {
+    _debouncer: Debouncer<RecommendedWatcher, RecommendedCache>,
+    receiver: Receiver<DebounceEventResult>,
+}
+
+impl SourceWatcher {
+    pub fn new(paths: &[&Path], debounce: Duration) -> notify_debouncer_full::notify::Result<Self> {
+        let (sender, receiver) = channel();
+        let mut debouncer = new_debouncer(debounce, None, sender)?;
+        for path in paths {
+            debouncer.watch(path, RecursiveMode::Recursive)?;
+        }
+
+        Ok(Self { _debouncer: debouncer, receiver })
+    }
+
+    /// Blocks until at least one file has been created, modified, removed or renamed.
+    pub fn next_changes(&self) -> Vec<Change> {
+        loop {
+            match self.receiver.recv() {
+                Ok(Ok(events)) => {
+                    let changes = to_changes(events);
+                    if !changes.is_empty() {
+                        return changes;
+                    }
+                }
+                Ok(Err(errors)) => {
+                    for error in errors {
+                        eprintln!("Watch error: {}", error);
+                    }
+                }
+                Err(_) => panic!("file watcher stopped unexpectedly"),
+            }
+        }
+    }
+}
+
+fn to_changes(events: Vec<DebouncedEvent>) -> Vec<Change> {
+    let mut changes: Vec<Change> = Vec::new();
+
+    for event in events {
+        let kind = match event.kind {
+            EventKind::Create(_) => ChangeKind::Created,
+            EventKind::Remove(_) => ChangeKind::Removed,
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                ChangeKind::Renamed { from: event.paths[0].clone() }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => ChangeKind::Removed,
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => ChangeKind::Created,
+            EventKind::Modify(ModifyKind::Metadata(_)) => continue,
+            EventKind::Modify(_) => ChangeKind::Modified,
+            EventKind::Access(_) | EventKind::Any | EventKind::Other => continue,
+        };
+
+        let Some(path) = event.paths.last().cloned() else { continue };
+
+        // a save usually emits create and several modify events for the same file
+        if let Some(existing) = changes.iter_mut().find(|change| change.path == path) {
+            existing.kind = match (&existing.kind, kind) {
+                (_, ChangeKind::Modified) => continue,
+                // editors often save by replacing the file
+                (ChangeKind::Removed, ChangeKind::Created) => ChangeKind::Modified,
+                (_, kind) => kind,
+            };
+            continue;
+        }
+
+        changes.push(Change { path, kind });
+    }
+
+    changes
+}
+
+/// The build steps that have to rerun in watch mode for a set of changes.
+#[derive(Debug, Default, PartialEq)]
+pub struct RebuildPlan {
+    pub css: bool,
+    pub assets: bool,
+    pub client: bool,
+    pub service: bool,
+}
+
+impl RebuildPlan {
+    pub fn all() -> Self {
+        Self { css: true, assets: true, client: true, service: true }
+    }
+
+    pub fn from_changes(assets_path: &Path, changes: &[Change]) -> Self {
+        let mut plan = Self::default();
+
+        for change in changes {
+            plan.add_path(assets_path, &change.path);
+            if let ChangeKind::Renamed { from } = &change.kind {
+                plan.add_path(assets_path, from);
+            }
+        }
+
+        plan
+    }
+
+    fn add_path(&mut self, assets_path: &Path, path: &Path) {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+        if path.starts_with(assets_path) {
+            // the service scans the assets folder and their ETags only at startup
+            self.assets = true;
+            self.service = true;
+        } else if matches!(extension, "scss" | "sass" | "css") {
+            self.css = true;
+        } else if file_name.ends_with(".client.ts") {
+            self.client = true;
+        } else {
+            self.client = true;
+            self.service = true;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_reports_created_modified_removed() {
+        let temp_dir = TempDir::new("watcher");
+        let dir = temp_dir.canonicalize().unwrap();
+        let watcher = SourceWatcher::new(&[&dir], Duration::from_millis(50)).unwrap();
+
+        let file = dir.join("style.scss");
+        fs::write(&file, "a {}").unwrap();
+        let changes = watcher.next_changes();
+        assert!(changes.iter().any(|c| c.path == file && c.kind == ChangeKind::Created), "{:?}", changes);
+
+        fs::write(&file, "b {}").unwrap();
+        let changes = watcher.next_changes();
+        assert!(changes.iter().any(|c| c.path == file && c.kind == ChangeKind::Modified), "{:?}", changes);
+
+        fs::remove_file(&file).unwrap();
+        let changes = watcher.next_changes();
+        assert!(changes.iter().any(|c| c.path == file && c.kind == ChangeKind::Removed), "{:?}", changes);
+    }
+
+    #[test]
+    fn test_rebuild_plan() {
+        let src = Path::new("/project/src");
+        let assets = src.join("assets");
+        let plan = |paths: &[&str]| RebuildPlan::from_changes(&assets, &paths.iter()
+            .map(|p| Change { path: src.join(p), kind: ChangeKind::Modified })
+            .collect::<Vec<_>>());
+
+        assert_eq!(plan(&["components/startpage/startpage.scss"]), RebuildPlan { css: true, ..Default::default() });
+        assert_eq!(plan(&["assets/favicon.ico"]), RebuildPlan { assets: true, service: true, ..Default::default() });
+        assert_eq!(plan(&["main.client.ts"]), RebuildPlan { client: true, ..Default::default() });
+        assert_eq!(plan(&["components/startpage/startpage.ts"]), RebuildPlan { client: true, service: true, ..Default::default() });
+        assert_eq!(plan(&["style.scss", "assets/test.txt"]), RebuildPlan { css: true, assets: true, service: true, ..Default::default() });
+
+        let renamed = RebuildPlan::from_changes(&assets, &[Change {
+            path: src.join("assets/logo.svg"),
+            kind: ChangeKind::Renamed { from: src.join("logo.svg") },
+        }]);
+        assert_eq!(renamed, RebuildPlan { assets: true, client: true, service: true, ..Default::default() });
+    }
+}
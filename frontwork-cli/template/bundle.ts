@@ -1,5 +1,5 @@
 import { APP_CONFIG } from './src/environments/environment.ts';
-import { frontwork_bundler } from "https://deno.land/x/frontwork@0.4.4/frontwork-bundler.ts";
+import { frontwork_bundler } from "https://deno.land/x/frontwork@0.5.0/frontwork-bundler.ts";
 
 const distdir = Deno.args[0] || "dist/development-web";
 const distdir_js = distdir + "/js/";
@@ -1 +1 @@
-export * from "https://deno.land/x/frontwork@0.4.4/lib.ts";
\ No newline at end of file
+export * from "https://deno.land/x/frontwork@0.5.0/lib.ts";
\ No newline at end of file
@@ -1,4 +1,4 @@
-import { FrontworkWebservice } from "https://deno.land/x/frontwork@0.4.4/frontwork-service.ts";
+import { FrontworkWebservice } from "https://deno.land/x/frontwork@0.5.0/frontwork-service.ts";
 import { APP_CONFIG } from "./environments/environment.ts";
 import { EnvironmentStage } from './dependencies.ts';
 
@@ -13,7 +13,8 @@ if(Deno.build.os === "windows" && __dir.charAt(0) === "/") __dir.substring(1, __
 if (APP_CONFIG.stage !== EnvironmentStage.Development || __dir.includes("/tmp/") || __dir.includes("/temp/")) {
     __dir = Deno.execPath().split("/").slice(0, -1).join("/");
 } else {
-    __dir_dist = __dir.substring(0, __dir.length - "/src".length) + "/dist/development-web"
+    // frontwork watch passes the dist folder of frontwork.toml
+    __dir_dist = Deno.env.get("FRONTWORK_DIST_DIR") || __dir.substring(0, __dir.length - "/src".length) + "/dist/development-web"
 }
 
 const __dir_dist_style_css = __dir_dist+"/css/style.css";
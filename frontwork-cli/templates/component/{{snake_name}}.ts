@@ -0,0 +1,26 @@
+import { Component, FrontworkContext, DocumentBuilder, FrontworkResponse, FrontworkClient } from "{{dependencies_path}}";
+
+
+export class {{class_name}} implements Component {
+    constructor(context: FrontworkContext) {
+        
+    }
+
+    async build(context: FrontworkContext) {
+        const document_builder = new DocumentBuilder(context);
+        const title = '{{camel_name}}';
+        const description = '{{camel_name}}';
+        
+        return await new FrontworkResponse(200, 
+            document_builder
+                .add_head_meta_data(title, description, "index,follow")
+        );
+    }
+
+    async dom_ready(context: FrontworkContext, client: FrontworkClient) {
+        
+    }
+    async on_destroy(context: FrontworkContext, client: FrontworkClient) {
+        
+    }
+}
@@ -0,0 +1,11 @@
+import { DocumentBuilder, FrontworkContext, ElemKit } from "{{dependencies_path}}";
+
+
+export class {{class_name}} extends DocumentBuilder {
+	main: ElemKit<HTMLElement>;
+
+	constructor(context: FrontworkContext) {
+		super(context);
+		this.main = this.body_append(context.create_element("main"));
+	}
+}
@@ -0,0 +1,11 @@
+import { BeforeRouteEvent, FrontworkContext, FrontworkClient } from "{{dependencies_path}}";
+
+
+/** Runs before the routes on the service and on the client; chained into before_route of the middleware in routes.ts */
+export const {{export_name}}: BeforeRouteEvent = {
+	// deno-lint-ignore require-await
+	build: async (_context: FrontworkContext) => {
+	},
+	dom_ready: (_context: FrontworkContext, _client: FrontworkClient) => {
+	},
+};
@@ -0,0 +1,8 @@
+import { FrontworkRequest } from "{{dependencies_path}}";
+import { FrontworkSubservice } from "{{service_module}}";
+
+
+/** Runs on the service before routing. Return a Response to answer the request, or null to continue with the routes. */
+export const {{export_name}}: FrontworkSubservice = async (request: FrontworkRequest, _req: Request, _req_extras: Deno.ServeHandlerInfo<Deno.NetAddr>) => {
+    return null;
+};
@@ -14,8 +14,14 @@ export async function frontwork_bundler(init: FrontworkInit, entryPoints: string
         }
     }
 
+    // "frontwork build" redirects environment.ts through an import map instead of modifying the source files
+    const import_map_url = Deno.env.get("FRONTWORK_IMPORT_MAP");
+    // tells "frontwork build" that the import map has been applied; bundlers of older versions ignore it
+    const import_map_receipt_path = Deno.env.get("FRONTWORK_IMPORT_MAP_RECEIPT");
+    if (import_map_url && import_map_receipt_path) await Deno.writeTextFile(import_map_receipt_path, import_map_url);
+
     await esbuild.build({
-        plugins: [...denoPlugins()],
+        plugins: [...denoPlugins(import_map_url ? { importMapURL: import_map_url } : {})],
         entryPoints: entryPoints,
         outdir: distdir_js,
         bundle: true,
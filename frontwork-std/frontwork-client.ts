@@ -25,6 +25,14 @@ export class FrontworkClient extends Frontwork {
         if (typeof init.build_on_page_load === "boolean") this.build_on_page_load = init.build_on_page_load;
         else this.build_on_page_load = false;
 
+        // the service rendered the hashed filenames into the document
+        const style_css_href = document.getElementById("fw-style")?.getAttribute("href");
+        if (style_css_href) FW.hashed_filenames["/css/style.css"] = style_css_href;
+        const main_js_src = document.getElementById("fw-script")?.getAttribute("src");
+        if (main_js_src) FW.hashed_filenames["/js/main.client.js"] = main_js_src;
+        const reload_port = document.getElementById("fw-script")?.getAttribute("data-reload-port");
+        if (reload_port) FW.reload_port = Number(reload_port);
+
         // DOM Ready
         document.addEventListener("DOMContentLoaded", () => {
             const request = new FrontworkRequest("GET", location.toString(), this.get_headers(), new PostScope({}));
@@ -122,10 +130,38 @@ export class FrontworkClient extends Frontwork {
               }
               
               connect();
-              
+
+            // reload channel of "frontwork watch"; notifies about rebuilds that do not restart the service
+            const connect_reload_channel = () => {
+                const ws = new WebSocket("ws://"+location.hostname+":"+FW.reload_port);
+                ws.onmessage = (event) => {
+                    if (event.data === "css-changed") {
+                        this.swap_stylesheet();
+                    } else if (event.data === "reload") {
+                        location.reload();
+                    }
+                };
+
+                ws.onclose = function() {
+                    setTimeout(connect_reload_channel, 3000);
+                };
+            }
+
+            connect_reload_channel();
         }
     }
 
+    /** Replaces the stylesheet without reloading the page. The old one is removed after the new one has been loaded to prevent a flash of unstyled content */
+    private swap_stylesheet() {
+        const old_style_css = document.getElementById("fw-style");
+        if (old_style_css === null) return;
+
+        const new_style_css = old_style_css.cloneNode() as HTMLLinkElement;
+        new_style_css.setAttribute("href", "/css/style.css?v=" + Date.now());
+        new_style_css.onload = () => old_style_css.remove();
+        old_style_css.after(new_style_css);
+    }
+
     
     private async page_change(request: FrontworkRequest, do_building: boolean, ignore_not_ready: boolean): Promise<PageChangeSavestate | null> {
         if (this.page_change_ready || ignore_not_ready) {
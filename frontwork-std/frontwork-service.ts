@@ -17,6 +17,8 @@ export class Asset {
     relative_path: string;
     content_type: string;
     etag: string;
+    /** true if the filename contains a content hash; the response may then be cached forever */
+    immutable = false;
 
     constructor(absolute_path: string, relative_file_path: string, content_type: string|null) {
         this.absolute_path = absolute_path;
@@ -80,18 +82,25 @@ export class Asset {
         return "unknown";
     }
 
+    /** Recomputes the ETag; needed when the file has changed while the service is running */
+    refresh_etag() {
+        this.etag = this.create_etag(this.absolute_path);
+    }
+
     private create_etag(absolute_path: string) {
         const fileInfo = Deno.statSync(absolute_path);
         return `W/"${fileInfo.size}-${fileInfo.mtime?.getTime() || ''}"`;
     }
 
-    create_file_response(request: FrontworkRequest, cache_max_age: number) {
+    create_file_response(request: FrontworkRequest, cache_max_age: number, requested_by_hashed_path = true) {
         if (FW.verbose_logging) request.log("ASSET", null);
         
         // Caching implementation:
         const headers: HeadersInit = {
             "content-type": this.content_type,
-            "Cache-Control": "public, max-age="+cache_max_age.toString()+", must-revalidate",
+            "Cache-Control": this.immutable && requested_by_hashed_path
+                ? "public, max-age=31536000, immutable"
+                : "public, max-age="+cache_max_age.toString()+", must-revalidate",
             "ETag": this.etag,
         };
         const cache_header = request.headers.get("if-none-match");
@@ -110,6 +119,9 @@ export class Asset {
     }
 }
 
+/** Written into the dist folder by "frontwork build". They describe the build and are never served. */
+export const BUILD_METADATA_FILES = ["hashed-filenames.json", "build-manifest.json"];
+
 /** FrontworkSubservice executed only on Deno server before Routung and before "before_route" */
 export interface FrontworkSubservice { (request: FrontworkRequest, _req: Request, _req_extras: Deno.ServeHandlerInfo<Deno.NetAddr>): Promise<Response | null> };
 
@@ -126,19 +138,34 @@ export class FrontworkWebservice extends Frontwork {
 
     private subservices: FrontworkSubservice[] = [];
 
+    /** original path => hashed path; see FW.hashed_filenames */
+    private hashed_filenames: { [path: string]: string } = {};
+    private hashed_paths = new Set<string>();
+
     constructor(init: FrontworkInit, dist_folder: string, style_css_path: string, main_js_path: string) {
         super(init);
         if (dist_folder.slice(-1) !== "/") dist_folder += "/";
 
-        this.style_css = new Asset(style_css_path, "/css/style.css", "text/css; charset=utf-8");
-        this.main_client_js = new Asset(main_js_path, "/js/main.client.js", "text/javascript; charset=utf-8");
+        this.read_hashed_filenames(dist_folder);
+        const style_css_hashed_path = this.hashed_filenames["/css/style.css"];
+        const main_js_hashed_path = this.hashed_filenames["/js/main.client.js"];
+        if (style_css_hashed_path) style_css_path = this.replace_filename(style_css_path, style_css_hashed_path);
+        // the sourcemap keeps its name, because the sourceMappingURL in main.client.js is not rewritten
+        const main_js_map_path = main_js_path + ".map";
+        if (main_js_hashed_path) main_js_path = this.replace_filename(main_js_path, main_js_hashed_path);
+
+        this.style_css = new Asset(style_css_path, style_css_hashed_path || "/css/style.css", "text/css; charset=utf-8");
+        this.main_client_js = new Asset(main_js_path, main_js_hashed_path || "/js/main.client.js", "text/javascript; charset=utf-8");
         if (this.stage === EnvironmentStage.Development) {
             this.assets.push(
-                new Asset(main_js_path+".map", "js/main.client.js.map", "application/json; charset=utf-8")
+                new Asset(main_js_map_path, "js/main.client.js.map", "application/json; charset=utf-8")
             )
+            // "frontwork watch" passes the port of its reload channel; the document hands it to the client
+            const reload_port = Deno.env.get("FRONTWORK_RELOAD_PORT");
+            if (reload_port) FW.reload_port = Number(reload_port);
         }
 
-        this.scan_directory(dist_folder, "/");
+        this.scan_directory(dist_folder, "/", BUILD_METADATA_FILES);
     }
 
     start() {
@@ -186,20 +213,41 @@ export class FrontworkWebservice extends Frontwork {
         globalThis.addEventListener("unload", () => abort_controller.abort());
     }
 
-    private scan_directory(dir_path: string, relative_path: string) {
+    /** Reads hashed-filenames.json, which is written by "frontwork build --hash-filenames" */
+    private read_hashed_filenames(dist_folder: string) {
+        try {
+            this.hashed_filenames = JSON.parse(Deno.readTextFileSync(dist_folder + "hashed-filenames.json"));
+        } catch {
+            return;
+        }
+
+        FW.hashed_filenames = this.hashed_filenames;
+        for (const path in this.hashed_filenames) {
+            this.hashed_paths.add(this.hashed_filenames[path]);
+        }
+    }
+
+    private replace_filename(absolute_path: string, hashed_path: string) {
+        return absolute_path.substring(0, absolute_path.lastIndexOf("/") + 1)
+            + hashed_path.substring(hashed_path.lastIndexOf("/") + 1);
+    }
+
+    /** @param excluded_files - file names in dir_path that are not served */
+    private scan_directory(dir_path: string, relative_path: string, excluded_files: string[] = []) {
         for (const dirEntry of Deno.readDirSync(dir_path)) {
             const absolute_path = dir_path + dirEntry.name;
             if (dirEntry.isFile 
                 && absolute_path !== this.style_css.absolute_path 
                 && absolute_path !== this.main_client_js.absolute_path
+                && !excluded_files.includes(dirEntry.name)
             ) {
-                this.assets.push(
-                    new Asset(
-                        absolute_path,
-                        relative_path + dirEntry.name,
-                        null
-                    ),
+                const asset = new Asset(
+                    absolute_path,
+                    relative_path + dirEntry.name,
+                    null
                 );
+                asset.immutable = this.hashed_paths.has(asset.relative_path);
+                this.assets.push(asset);
             } else if (dirEntry.isDirectory) {
                 this.scan_directory(
                     dir_path + dirEntry.name + "/",
@@ -240,9 +288,14 @@ export class FrontworkWebservice extends Frontwork {
     }
 
     private async assets_resolver(request: FrontworkRequest): Promise<Response | null> {
-        if (request.path === this.style_css.relative_path) {
+        // the original path of a hashed file is still served, but without the immutable caching
+        const hashed_path = this.hashed_filenames[request.path];
+        const path = hashed_path || request.path;
+        const requested_by_hashed_path = hashed_path === undefined;
+
+        if (path === this.style_css.relative_path) {
             try {
-                return this.style_css.create_file_response(request, this.cache_max_age);
+                return this.style_css.create_file_response(request, this.cache_max_age, requested_by_hashed_path);
                 // deno-lint-ignore no-explicit-any
             } catch (error: any) {
                 FW.reporter(
@@ -255,9 +308,9 @@ export class FrontworkWebservice extends Frontwork {
                 );
                 return null;
             }
-        } else if (request.path === this.main_client_js.relative_path) {
+        } else if (path === this.main_client_js.relative_path) {
             try {
-                return this.main_client_js.create_file_response(request, this.cache_max_age);
+                return this.main_client_js.create_file_response(request, this.cache_max_age, requested_by_hashed_path);
                 // deno-lint-ignore no-explicit-any
             } catch (error: any) {
                 FW.reporter(
@@ -273,9 +326,9 @@ export class FrontworkWebservice extends Frontwork {
         }
 
         for (const asset of this.assets) {
-            if (asset.relative_path === request.path) {
+            if (asset.relative_path === path) {
                 try {
-                    return asset.create_file_response(request, this.cache_max_age);
+                    return asset.create_file_response(request, this.cache_max_age, requested_by_hashed_path);
                     // deno-lint-ignore no-explicit-any
                 } catch (error: any) {
                     FW.reporter(
@@ -424,6 +477,12 @@ export class FrontworkWebservice extends Frontwork {
             return new Response("Browser FW.reporter => Dev Server reported");
         }
 
+        // "frontwork watch" may rebuild style.css and main.client.js without restarting this service
+        if (this.stage === EnvironmentStage.Development) {
+            this.style_css.refresh_etag();
+            this.main_client_js.refresh_etag();
+        }
+
         for (let i = 0; i < this.api_path_prefixes.length; i++) {
             const api_path_prefix = this.api_path_prefixes[i];
             if (url.pathname.substring(0, api_path_prefix.length) === api_path_prefix) {
@@ -24,6 +24,19 @@ export const FW =  {
      * Warn and Error messages will always be reported.
      */
     verbose_logging: false,
+
+    /**
+     * Port of the reload channel of `frontwork watch`. The client connects to it in the development environment
+     * to swap the stylesheet or reload the page after a rebuild. The service reads the port of watch.reload_port
+     * from FRONTWORK_RELOAD_PORT and renders it into the document for the client.
+     */
+    reload_port: 35729,
+
+    /**
+     * Maps original paths like "/css/style.css" to their content hashed paths.
+     * Filled by FrontworkWebservice and FrontworkClient if the project has been built with "frontwork build --hash-filenames"
+     */
+    hashed_filenames: {} as { [path: string]: string },
     
     /**
      * To enable a bug reporter for staging and production you can modify FW.reporter, that it sents a request to the backend
@@ -512,13 +525,14 @@ export class DocumentBuilder implements DocumentBuilderInterface {
             const style_css = this.context.head.elem.appendChild( document.createElement("link") );
             style_css.setAttribute("id", "fw-style");
             style_css.setAttribute("rel", "stylesheet");
-            style_css.setAttribute("href", "/css/style.css");
+            style_css.setAttribute("href", FW.hashed_filenames["/css/style.css"] || "/css/style.css");
             style_css.setAttribute("type", "text/css");
     
             // force adding main.client.js to the end of the body
             const main_js = this.context.body.elem.appendChild( document.createElement("script") );
             main_js.setAttribute("id", "fw-script");
-            main_js.setAttribute("src", "/js/main.client.js");
+            main_js.setAttribute("src", FW.hashed_filenames["/js/main.client.js"] || "/js/main.client.js");
+            if (this.context.stage === EnvironmentStage.Development) main_js.setAttribute("data-reload-port", String(FW.reload_port));
 			if (this.context.module_splitting) {
 				main_js.setAttribute("type", "module");
 			} else {
@@ -570,6 +584,18 @@ export declare interface BeforeRouteEvent {
     dom_ready: DomReadyEvent;
 }
 
+/** Runs several before_route events in order, e.g. the ones created by "frontwork generate middleware" */
+export function chain_before_route(...events: BeforeRouteEvent[]): BeforeRouteEvent {
+    return {
+        build: async (context: FrontworkContext) => {
+            for (const event of events) await event.build(context);
+        },
+        dom_ready: (context: FrontworkContext, client: FrontworkClient) => {
+            for (const event of events) event.dom_ready(context, client);
+        },
+    };
+}
+
 
 let previous_route_id = 0;
 export class Route {
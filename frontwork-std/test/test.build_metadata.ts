@@ -0,0 +1,28 @@
+// deno test --allow-read --allow-write --allow-env frontwork-std/test/test.build_metadata.ts
+import { Asset, BUILD_METADATA_FILES, FrontworkWebservice } from "../frontwork-service.ts";
+import { APP_CONFIG } from "./test.routes.ts";
+
+
+Deno.test("the build metadata in the dist folder is not served", () => {
+    const dist_folder = Deno.makeTempDirSync({ prefix: "frontwork-dist-" }) + "/";
+    try {
+        Deno.mkdirSync(dist_folder + "css");
+        Deno.mkdirSync(dist_folder + "js");
+        Deno.mkdirSync(dist_folder + "assets");
+        Deno.writeTextFileSync(dist_folder + "css/style.css", "");
+        Deno.writeTextFileSync(dist_folder + "js/main.client.js", "");
+        Deno.writeTextFileSync(dist_folder + "assets/build-manifest.json", "{}");
+        for (const file of BUILD_METADATA_FILES) Deno.writeTextFileSync(dist_folder + file, "{}");
+
+        const service = new FrontworkWebservice(APP_CONFIG, dist_folder, dist_folder + "css/style.css", dist_folder + "js/main.client.js");
+        const served_paths = (service as unknown as { assets: Asset[] }).assets.map((asset) => asset.relative_path);
+
+        for (const file of BUILD_METADATA_FILES) {
+            if (served_paths.includes("/" + file)) throw new Error("/" + file + " is served");
+        }
+        // only the files in the root of the dist folder are build metadata
+        if (!served_paths.includes("/assets/build-manifest.json")) throw new Error("/assets/build-manifest.json is not served");
+    } finally {
+        Deno.removeSync(dist_folder, { recursive: true });
+    }
+});